@@ -1,16 +1,25 @@
+use log::{debug, error, info, warn};
 use md5;
-use serde::{Deserialize, Serialize};
+use serde::{
+    de::{DeserializeOwned, IgnoredAny},
+    Deserialize, Serialize,
+};
 use std::cmp::Ordering;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
-use std::time::SystemTime;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering as AtomicOrdering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
 use tauri::{
-    menu::{Menu, MenuEvent, MenuItem},
+    menu::{Menu, MenuEvent, MenuItem, Submenu},
     tray::{TrayIconBuilder, TrayIconEvent},
-    Manager, State,
+    Emitter, Manager, State,
 };
+use tauri_plugin_autostart::{MacosLauncher, ManagerExt};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+use tauri_plugin_notification::{NotificationExt, PermissionState};
+use tauri_plugin_store::StoreExt;
 
 #[cfg(target_os = "windows")]
 use winreg::{
@@ -27,10 +36,68 @@ struct GithubContent {
     download_url: Option<String>,
 }
 
+/// Error returned by commands, serialized as `{ "code": "DotaRunning",
+/// "message": "..." }` so the UI can react to the kind of failure and still
+/// show the message.
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "code", content = "message")]
+pub enum AppError {
+    NetworkError(String),
+    RateLimited(String),
+    ConfigNotFound(String),
+    IoError(String),
+    PermissionDenied(String),
+    HashMismatch(String),
+    DotaRunning(String),
+    AntivirusSuspected(String),
+    Other(String),
+}
+
+impl AppError {
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::NetworkError(message)
+            | AppError::RateLimited(message)
+            | AppError::ConfigNotFound(message)
+            | AppError::IoError(message)
+            | AppError::PermissionDenied(message)
+            | AppError::HashMismatch(message)
+            | AppError::DotaRunning(message)
+            | AppError::AntivirusSuspected(message)
+            | AppError::Other(message) => message,
+        }
+    }
+}
+
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+// Lets commands returning AppError use `?` on the String-returning helpers
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Other(message)
+    }
+}
+
+// And lets String-returning code use `?` on AppError-returning functions
+impl From<AppError> for String {
+    fn from(error: AppError) -> Self {
+        error.message().to_string()
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Grid {
     pub name: String,
-    pub date: String,
+    pub date: String, // YYYY-MM-DD, or "Unknown"
+    #[serde(default)]
+    pub parsed_date: Option<chrono::NaiveDate>, // None when `date` is "Unknown"
+    pub patch: Option<String>, // e.g. "7.41c"
+    #[serde(default)]
+    pub grid_type: String, // "d2pt", "high_winrate", "most_played" or "unknown"
     pub download_url: String,
 }
 
@@ -38,272 +105,6174 @@ pub struct Grid {
 pub struct DetectedGrid {
     pub grid_type: String, // "d2pt", "high_winrate", "most_played", or "custom"
     pub name: String,
-    pub date: String,
-    pub hash: String,
+    pub date: String, // YYYY-MM-DD, or "Unknown"
+    #[serde(default)]
+    pub parsed_date: Option<chrono::NaiveDate>,
+    pub hash: String,             // the hash that matched, else the normalized hash
+    pub raw_hash: Option<String>, // hash of the file bytes exactly as stored
+    pub normalized_hash: Option<String>, // hash without BOM, CRs or trailing whitespace
+    pub matched_form: Option<String>, // "raw" or "normalized" when known
     pub is_known: bool,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GridMatch {
+    #[serde(flatten)]
+    pub grid: DetectedGrid,
+    pub newest: Option<Grid>, // newest published grid of the same type
+    pub newer_available: bool,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GridHashes {
     pub hashes: HashMap<String, String>, // filename -> hash
 }
 
-#[tauri::command]
-fn find_dota_config_path() -> Result<Option<PathBuf>, String> {
-    let mut steam_path: Option<PathBuf> = None;
+// Typed view of hero_grid_config.json. Only the fields the app reads are typed;
+// everything else (layout, future additions) is kept in `extra` so it survives
+// a parse/serialize round trip.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HeroGridConfig {
+    pub configs: Vec<GridConfigPage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<u64>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
 
-    #[cfg(target_os = "windows")]
-    {
-        if let Ok(hklm) = RegKey::predef(HKEY_LOCAL_MACHINE).open_subkey_with_flags(
-            "SOFTWARE\\Wow6432Node\\Valve\\Steam",
-            winreg::enums::KEY_READ,
-        ) {
-            if let Ok(path_str) = hklm.get_value::<String, _>("InstallPath") {
-                steam_path = Some(PathBuf::from(path_str));
-            }
-        }
-        if steam_path.is_none() {
-            if let Ok(hkcu) = RegKey::predef(HKEY_CURRENT_USER)
-                .open_subkey_with_flags("Software\\Valve\\Steam", winreg::enums::KEY_READ)
-            {
-                if let Ok(path_str) = hkcu.get_value::<String, _>("SteamPath") {
-                    steam_path = Some(PathBuf::from(path_str));
-                } else if let Ok(path_str) = hkcu.get_value::<String, _>("InstallPath") {
-                    steam_path = Some(PathBuf::from(path_str));
-                }
-            }
-        }
-    }
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GridConfigPage {
+    pub config_name: String,
+    pub categories: Vec<GridCategory>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
 
-    #[cfg(not(target_os = "windows"))]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GridCategory {
+    pub category_name: String,
+    pub hero_ids: Vec<u32>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigSummary {
+    pub config_name: String,
+    pub category_count: usize,
+    pub hero_count: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MergePreview {
+    pub added: Vec<String>,
+    pub replaced: Vec<String>,
+    pub summary: Vec<ConfigSummary>,
+    pub result: HeroGridConfig,
+    // Set when the installed config couldn't be parsed; the result is then
+    // just the incoming grid, as a merge would replace the whole file
+    pub current_parse_error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PinnedGrid {
+    pub name: String,
+    pub download_url: String,
+    pub hash: String,
+    pub pinned_at: u64, // unix timestamp (seconds)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DefaultGridStatus {
+    pub is_default: bool,
+    pub state: String, // "missing", "empty", "default" or "custom"
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CategoryChange {
+    pub config_name: String,
+    pub hero_id: u32,
+    pub hero_name: String,
+    pub old_category: String,
+    pub new_category: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RenamedConfig {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CategoryCountDiff {
+    pub config_name: String,
+    pub category_name: String,
+    pub count_a: Option<usize>, // None when the category is missing from grid A
+    pub count_b: Option<usize>,
+    pub delta: i64, // count_b - count_a, a missing category counting as 0
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PageDiff {
+    pub config_name: String,    // name in the remote grid
+    pub installed_name: String, // may differ by patch, e.g. "... 7.39e - Carry"
+    pub heroes_moved: usize,    // heroes in a different category
+    pub heroes_added: usize,    // heroes only on the remote page
+    pub heroes_removed: usize,  // heroes only on the installed page
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GridDiff {
+    pub added: Vec<String>,   // remote pages with no installed counterpart
+    pub removed: Vec<String>, // installed pages the remote grid doesn't have
+    pub changed: Vec<PageDiff>,
+    pub unchanged: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigCountStatus {
+    pub count: usize,
+    pub limit: usize,
+    pub near_limit: bool, // within CONFIG_COUNT_WARNING_MARGIN of the limit, or over it
+    pub warning: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GridValidation {
+    pub valid: bool,
+    pub version: Option<u64>,
+    pub config_count: usize,
+    pub errors: Vec<String>,
+    pub hash: Option<String>, // md5 of the file, when validating a file
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SnapshotEntry {
+    pub user_id: String,
+    pub config_path: String,
+    pub hash: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SnapshotSet {
+    pub id: String,
+    pub label: Option<String>,
+    pub created_at: u64, // unix timestamp (seconds)
+    pub entries: Vec<SnapshotEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RoundtripResult {
+    pub passed: bool,
+    pub expected_hash: String,
+    pub actual_hash: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GridBackup {
+    pub name: String,    // file name inside the cfg directory
+    pub created_at: u64, // unix timestamp (milliseconds), from the file name
+    pub size_bytes: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BackupSession {
+    pub id: String,
+    pub name: String,
+    pub created_at: u64,      // unix timestamp (seconds)
+    pub backups: Vec<String>, // backup file paths
+}
+
+/// Marker recorded when the app writes a grid, stored per cfg directory in
+/// managed_grids.json in the app data dir, e.g.
+/// `{"/path/to/570/remote/cfg": {"hash": "...", "grid_name": "...", "stamped_at": 1760000000}}`.
+/// Nothing is added to Dota's folder, and deleting the file removes all
+/// markers.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ManagedGridMarker {
+    pub hash: String, // md5 of the grid as written
+    pub grid_name: String,
+    pub stamped_at: u64, // unix timestamp (seconds)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HistoryEntry {
+    pub timestamp: u64, // unix timestamp (seconds)
+    pub grid_name: String,
+    pub grid_type: String,
+    pub date: String,
+    pub hash: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UndoPreview {
+    pub backup_path: String,
+    pub size_bytes: u64,
+    pub configs: Vec<ConfigSummary>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AutoBackupStatus {
+    pub interval_hours: u64,         // 0 when scheduled backups are off
+    pub last_backup_at: Option<u64>, // unix timestamp (seconds)
+    pub last_backup_path: Option<String>,
+    pub last_hash: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PruneReport {
+    pub files_removed: usize,
+    pub bytes_removed: u64,
+}
+
+#[derive(Deserialize, Debug)]
+struct GithubRateLimitResponse {
+    resources: GithubRateLimitResources,
+}
+
+#[derive(Deserialize, Debug)]
+struct GithubRateLimitResources {
+    core: GithubRateLimit,
+}
+
+#[derive(Deserialize, Debug)]
+struct GithubRateLimit {
+    limit: u64,
+    remaining: u64,
+    used: u64,
+    reset: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RateLimitStatus {
+    pub limit: u64,
+    pub remaining: u64,
+    pub used: u64,
+    pub reset_at: u64, // unix timestamp (seconds)
+    pub seconds_until_reset: u64,
+    pub authenticated: bool,
+}
+
+#[derive(Deserialize, Debug)]
+struct OpenDotaHero {
+    id: u32,
+    localized_name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DetectionStatus {
+    // "steam_not_found", "userdata_missing", "userdata_empty", "dota_cfg_missing" or "found"
+    pub status: String,
+    pub steam_path: Option<String>,
+    pub config_path: Option<String>,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SourceInfoCache {
+    pub fetched_at: u64,        // unix timestamp (seconds)
+    pub readme: Option<String>, // None when the repo has no README
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GridListCache {
+    pub fetched_at: u64, // unix timestamp (seconds)
+    pub grids: Vec<Grid>,
+    #[serde(default)]
+    pub etag: Option<String>, // GitHub's ETag for `grids`, sent as If-None-Match
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RemoteGridList {
+    pub grids: Vec<Grid>,
+    pub fetched_at: u64, // unix timestamp (seconds) of the GitHub fetch
+    pub stale: bool,     // served from an expired cache because GitHub failed
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GridListDiff {
+    pub added: Vec<Grid>,
+    pub removed: Vec<Grid>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GridCoverage {
+    pub grid: Grid,
+    pub covered: usize,
+    pub roster_size: usize,
+    pub score: f64, // covered / roster_size
+    pub missing_hero_ids: Vec<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GridSuggestion {
+    pub best: Option<GridCoverage>,
+    pub ranking: Vec<GridCoverage>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StaleHeroReference {
+    pub hero_id: u32,
+    pub categories: Vec<String>, // "config / category" for each place it appears
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StaleHeroReport {
+    pub stale: Vec<StaleHeroReference>,
+    pub roster_size: usize,
+    pub suggested_update: Option<Grid>, // newer grid of the same type, if known
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UpdateCheckResult {
+    pub current: Option<DetectedGrid>,
+    pub latest: Option<Grid>,
+    pub update_available: bool,
+    pub clock_skew_warning: Option<String>,
+    pub skipped_reason: Option<String>, // set when the check didn't run, e.g. on battery
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConservativeActivation {
+    pub applied: bool,
+    pub reason: String,
+    pub grid: Option<Grid>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ClockSkew {
+    pub skew_seconds: i64, // positive when the local clock is ahead
+    pub exceeds_threshold: bool,
+    pub warning: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AutoUpdateStatus {
+    pub last_run: u64,   // unix timestamp (seconds) of the last scheduler tick
+    pub outcome: String, // "checked", "updated", "skipped" or "failed"
+    #[serde(default)]
+    pub last_checked: Option<u64>, // last tick that wasn't skipped; paces the battery policy
+    pub update_available: bool,
+    pub current_grid: Option<String>,
+    pub latest_grid: Option<String>,
+    pub error: Option<String>,
+    #[serde(default)]
+    pub skipped_reason: Option<String>,
+    #[serde(default)]
+    pub power_state: Option<String>, // "ac", "battery" or "unknown", as of now
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProfileCount {
+    pub total: usize,    // every userdata/<id> directory, including "0"
+    pub with_cfg: usize, // profiles with a Dota 2 cfg directory, excluding "0"
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SteamAccount {
+    pub user_id: String,
+    pub persona_name: Option<String>, // from the profile's localconfig.vdf
+    pub config_path: String,
+    pub last_modified: Option<u64>, // hero_grid_config.json mtime, unix seconds
+    pub active: bool,               // the cfg directory detection and activation use
+    pub duplicate: bool,            // another listed cfg directory belongs to the same account
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CfgDirEntry {
+    pub user_id: String,
+    pub app_id: String, // "570", or an extra app id such as a test client
+    pub path: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProfileGrid {
+    pub user_id: String,
+    pub config_path: String,
+    pub grid: Option<DetectedGrid>, // None when the profile has no grid file
+    pub mismatch: bool,             // differs from the grid most profiles have
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DetectionBenchmark {
+    pub find_config_path_ms: f64,
+    pub detect_current_grid_ms: f64,
+    pub total_ms: f64,
+    pub profiles_scanned: usize,
+    pub config_path: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SmartActivationResult {
+    pub hash: String,
+    pub attempts: u32,
+    pub cloud_conflict: bool, // true when a write was reverted at least once
+    #[serde(default)]
+    pub verified: bool, // false when grid_hashes.txt has no entry for the grid
+    pub warning: Option<String>,
+    pub write_strategy: String, // "rename" or "copy", see write_file_atomic_with_strategy
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GridActivation {
+    pub hash: String, // MD5 of the installed file, or of the file a dry run would write
+    pub verified: bool, // false when grid_hashes.txt has no entry for the grid
+    pub warning: Option<String>,
+    pub dry_run: Option<DryRunReport>, // set when nothing was written
+    pub pages: Vec<String>,            // config names of the downloaded pages, as installed
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DryRunReport {
+    pub target_path: String,
+    pub would_overwrite: bool, // a grid is already installed there (and would be backed up)
+    pub validation: GridValidation,
+}
+
+// Payload of the "grid-download-progress" event
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DownloadProgress {
+    pub download_url: String,
+    pub downloaded: u64,    // bytes received so far
+    pub total: Option<u64>, // from Content-Length, when the server sends it
+    pub status: String,     // "downloading", "done" or "failed"
+    pub error: Option<String>,
+}
+
+// Payload of the "grid-applied" event, emitted by every activation path
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GridAppliedEvent {
+    pub name: String,
+    pub grid_type: String,
+    pub hash: String,
+    pub backup_made: bool,
+    pub config_path: String, // the file that was written
+}
+
+const DOTA_APP_ID: &str = "570";
+const GRID_FILE_NAME: &str = "hero_grid_config.json";
+// hero_grid_config.json "version" values this app knows how to read and write
+const SUPPORTED_GRID_VERSIONS: &[u64] = &[3];
+const SETTINGS_STORE: &str = "settings.json";
+// Kept apart from the settings so they can be shared without leaking credentials
+const SECRETS_STORE: &str = "secrets.json";
+const GITHUB_TOKEN_REJECTED: &str =
+    "GitHub rejected the access token (401 Unauthorized). Check it or clear it in settings.";
+const DEFAULT_GRID_REPO: &str = "abnersajr/d2pt-grid-updater";
+const DEFAULT_GRID_BRANCH: &str = "main";
+
+const TRAY_ID: &str = "main";
+
+// How long the cached repo README is shown before being fetched again
+const SOURCE_INFO_TTL_SECS: u64 = 6 * 60 * 60;
+// How long list_remote_grids serves the cached list without asking GitHub
+const GRID_LIST_TTL_SECS: u64 = 15 * 60;
+const DEFAULT_UPDATE_CHECK_INTERVAL_HOURS: u64 = 6;
+// Attempts per network request, including the first one
+const DEFAULT_NETWORK_RETRY_ATTEMPTS: u32 = 3;
+// First retry delay, doubled on each further attempt
+const RETRY_BASE_DELAY_MS: u64 = 500;
+// Upper bound on any single retry delay, including a server's Retry-After
+const MAX_RETRY_DELAY_SECS: u64 = 60;
+
+// What a fresh Dota install writes before the user creates any grid
+const DEFAULT_GRID_FINGERPRINT: &str = r#"{"version":3,"configs":[]}"#;
+
+// Minimal valid grid used by verify_write_roundtrip
+const SELF_TEST_GRID: &str = r#"{
+  "configs": [
     {
-        let home_dir = dirs::home_dir().ok_or("Could not find home directory")?;
-        let possible_steam_paths = vec![
-            home_dir.join(".steam").join("steam"),
-            home_dir.join(".local").join("share").join("Steam"),
-            home_dir
-                .join("Library")
-                .join("Application Support")
-                .join("Steam"),
-        ];
-        for p in possible_steam_paths {
-            let dota_check_path = p.join("steamapps").join("common").join("dota 2 beta");
-            if dota_check_path.exists() && dota_check_path.is_dir() {
-                steam_path = Some(p);
-                break;
-            } else if p.exists() && p.is_dir() {
-                steam_path = Some(p);
-                break;
-            }
+      "config_name": "D2PT Grid Updater self-test",
+      "categories": [
+        {
+          "category_name": "Test",
+          "x_position": 0,
+          "y_position": 0,
+          "width": 100,
+          "height": 100,
+          "hero_ids": [1, 2, 3]
         }
+      ]
     }
+  ],
+  "version": 3
+}
+"#;
 
-    let steam_path = match steam_path {
-        Some(p) => p,
-        None => return Ok(None),
-    };
+// How long to wait before re-reading the grid to see if Steam Cloud or an
+// antivirus reverted it
+const DEFAULT_WRITE_CHECK_WINDOW_MS: u64 = 3000;
+const MAX_WRITE_CHECK_WINDOW_MS: u64 = 60_000;
+const DEFAULT_CLOUD_RETRY_COUNT: u32 = 1;
 
-    let userdata_path = steam_path.join("userdata");
-    if !userdata_path.exists() || !userdata_path.is_dir() {
-        return Ok(None);
-    }
+// Plausible hero id range; ids outside it mean a corrupt grid. Raise the
+// upper bound as Valve adds heroes.
+const MIN_HERO_ID: u32 = 1;
+const MAX_HERO_ID: u32 = 160;
 
-    let mut latest_cfg_path: Option<PathBuf> = None;
-    let mut latest_mod_time: Option<SystemTime> = None;
+// How many configs Dota's grid picker can show; configs past it become
+// unreachable in game. Raise it if Valve does.
+const MAX_GRID_CONFIGS: usize = 32;
+const CONFIG_COUNT_WARNING_MARGIN: usize = 4;
 
-    for entry in fs::read_dir(&userdata_path).map_err(|e| e.to_string())? {
-        let entry = entry.map_err(|e| e.to_string())?;
-        let user_id_path = entry.path();
-        if user_id_path.is_dir() {
-            let dota_cfg_dir = user_id_path.join("570").join("remote").join("cfg");
-            if dota_cfg_dir.exists() && dota_cfg_dir.is_dir() {
-                let hero_grid_path = dota_cfg_dir.join("hero_grid_config.json");
-                if hero_grid_path.exists() {
-                    if let Ok(metadata) = fs::metadata(&hero_grid_path) {
-                        if let Ok(mod_time) = metadata.modified() {
-                            if let Some(latest) = latest_mod_time {
-                                if mod_time.cmp(&latest) == Ordering::Greater {
-                                    latest_mod_time = Some(mod_time);
-                                    latest_cfg_path = Some(dota_cfg_dir.clone());
-                                }
-                            } else {
-                                latest_mod_time = Some(mod_time);
-                                latest_cfg_path = Some(dota_cfg_dir.clone());
-                            }
-                        }
-                    }
-                } else {
-                    if latest_cfg_path.is_none() {
-                        latest_cfg_path = Some(dota_cfg_dir);
-                    }
-                }
-            }
-        }
-    }
-    Ok(latest_cfg_path)
+// Minimum time between update checks on battery with the "reduced" policy
+const BATTERY_CHECK_INTERVAL_SECS: u64 = 4 * 60 * 60;
+
+// Local clock drift beyond this makes grid date comparisons unreliable
+const CLOCK_SKEW_THRESHOLD_SECS: i64 = 60 * 60;
+
+fn read_setting<T: DeserializeOwned>(app: &tauri::AppHandle, key: &str) -> Option<T> {
+    let store = app.store(SETTINGS_STORE).ok()?;
+    store
+        .get(key)
+        .and_then(|value| serde_json::from_value(value).ok())
 }
 
-#[tauri::command]
-async fn list_remote_grids() -> Result<Vec<Grid>, String> {
-    let client = reqwest::Client::new();
-    let api_url = "https://api.github.com/repos/abnersajr/d2pt-grid-updater/contents/grids";
-    let response = client
-        .get(api_url)
-        .header("User-Agent", "d2pt-grid-updater-app")
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
-    if !response.status().is_success() {
-        return Err(format!(
-            "Failed to fetch grids from GitHub: {}",
-            response.status()
-        ));
+fn write_setting<T: Serialize>(app: &tauri::AppHandle, key: &str, value: T) -> Result<(), String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+    let value = serde_json::to_value(value).map_err(|e| e.to_string())?;
+    store.set(key, value);
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+// Filename format: dota2protracker_hero_grid_[type]_config_[date]_p[major]_[minor].json
+fn classify_grid_type(filename: &str) -> &'static str {
+    if filename.contains("d2pt_rating") {
+        "d2pt"
+    } else if filename.contains("high_winrate") {
+        "high_winrate"
+    } else if filename.contains("most_played") {
+        "most_played"
+    } else {
+        "unknown"
     }
-    let contents: Vec<GithubContent> = response.json().await.map_err(|e| e.to_string())?;
-    let grids: Vec<Grid> = contents
-        .into_iter()
-        .filter_map(|item| {
+}
+
+/// The publish date in a grid filename: the first token that is a valid
+/// YYYY-MM-DD or YYYYMMDD date. Patch tokens like "p7" never match.
+fn parse_grid_naive_date(filename: &str) -> Option<chrono::NaiveDate> {
+    let stem = filename.strip_suffix(".json").unwrap_or(filename);
+    stem.split('_').find_map(|token| {
+        chrono::NaiveDate::parse_from_str(token, "%Y-%m-%d")
+            .ok()
+            .or_else(|| {
+                (token.len() == 8 && token.chars().all(|c| c.is_ascii_digit()))
+                    .then(|| chrono::NaiveDate::parse_from_str(token, "%Y%m%d").ok())
+                    .flatten()
+            })
+    })
+}
+
+/// The publish date as an ISO YYYY-MM-DD string, or "Unknown".
+fn parse_grid_date(filename: &str) -> String {
+    parse_grid_naive_date(filename)
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
+fn parse_grid_patch(filename: &str) -> Option<String> {
+    let stem = filename.strip_suffix(".json").unwrap_or(filename);
+    let parts: Vec<&str> = stem.split('_').collect();
+    let index = parts.iter().position(|p| {
+        p.len() > 1 && p.starts_with('p') && p[1..].chars().all(|c| c.is_ascii_digit())
+    })?;
+    let minor = parts.get(index + 1)?;
+    Some(format!("{}.{}", &parts[index][1..], minor))
+}
+
+const RANK_BRACKETS: &[&str] = &[
+    "herald", "guardian", "crusader", "archon", "legend", "ancient", "divine", "immortal",
+];
+
+/// Rank-specific grids carry the bracket as its own filename token
+/// (e.g. ..._d2pt_rating_immortal_config_...). General grids have none.
+fn parse_grid_rank(filename: &str) -> Option<&'static str> {
+    let stem = filename.strip_suffix(".json").unwrap_or(filename);
+    stem.split('_').find_map(|part| {
+        RANK_BRACKETS
+            .iter()
+            .copied()
+            .find(|bracket| part.eq_ignore_ascii_case(bracket))
+    })
+}
+
+/// Reduces "7.41c", "741c" and "p7_41c" to the same comparable form.
+fn normalize_patch(patch: &str) -> String {
+    patch
+        .trim()
+        .trim_start_matches(['p', 'P'])
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_lowercase()
+}
+
+fn github_token(app: &tauri::AppHandle) -> Option<String> {
+    let store = app.store(SECRETS_STORE).ok()?;
+    store
+        .get("github_token")
+        .and_then(|value| value.as_str().map(str::to_string))
+        .filter(|token| !token.is_empty())
+}
+
+fn write_github_token(app: &tauri::AppHandle, token: Option<&str>) -> Result<(), String> {
+    let store = app
+        .store(SECRETS_STORE)
+        .map_err(|e| format!("Failed to open secrets store: {}", e))?;
+    match token {
+        Some(token) => store.set("github_token", token),
+        None => {
+            store.delete("github_token");
+        }
+    }
+    store
+        .save()
+        .map_err(|e| format!("Failed to save secrets: {}", e))
+}
+
+/// Moves a token saved by older versions out of settings.json, which is meant
+/// to be safe to share when reporting issues.
+fn migrate_github_token(app: &tauri::AppHandle) -> Result<(), String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+    let Some(value) = store.get("github_token") else {
+        return Ok(());
+    };
+    if let Some(token) = value.as_str().filter(|token| !token.is_empty()) {
+        write_github_token(app, Some(token))?;
+    }
+    store.delete("github_token");
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+fn github_request(
+    client: &reqwest::Client,
+    url: &str,
+    token: Option<&str>,
+) -> reqwest::RequestBuilder {
+    let request = client
+        .get(url)
+        .header("User-Agent", "d2pt-grid-updater-app");
+    match token {
+        Some(token) => request.bearer_auth(token),
+        None => request,
+    }
+}
+
+#[tauri::command]
+fn get_network_retry_attempts(app: tauri::AppHandle) -> u32 {
+    read_setting(&app, "network_retry_attempts").unwrap_or(DEFAULT_NETWORK_RETRY_ATTEMPTS)
+}
+
+/// Sets how many times a network request is attempted before giving up;
+/// 1 disables retries.
+#[tauri::command]
+fn set_network_retry_attempts(app: tauri::AppHandle, attempts: u32) -> Result<(), AppError> {
+    info!("Setting network_retry_attempts to: {}", attempts);
+    write_setting(&app, "network_retry_attempts", attempts.max(1)).map_err(AppError::IoError)
+}
+
+// Shared HTTP client, so connections are reused and every request goes
+// through the same proxy configuration
+pub struct HttpClient {
+    pub client: Mutex<reqwest::Client>,
+}
+
+/// Builds the HTTP client. HTTP_PROXY, HTTPS_PROXY and NO_PROXY from the
+/// environment are honored; an explicit `proxy_url` takes precedence over the
+/// first two but still respects NO_PROXY.
+fn build_http_client(proxy_url: Option<&str>) -> Result<reqwest::Client, String> {
+    let mut builder = reqwest::Client::builder();
+    if let Some(proxy_url) = proxy_url {
+        let proxy = reqwest::Proxy::all(proxy_url)
+            .map_err(|e| format!("Invalid proxy URL {}: {}", proxy_url, e))?
+            .no_proxy(reqwest::NoProxy::from_env());
+        builder = builder.proxy(proxy);
+    }
+    builder
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+fn http_client(app: &tauri::AppHandle) -> reqwest::Client {
+    app.state::<HttpClient>().client.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn get_proxy_url(app: tauri::AppHandle) -> Option<String> {
+    read_setting(&app, "proxy_url")
+}
+
+/// Sets an explicit proxy for all downloads (e.g. "http://proxy:8080"), or
+/// goes back to the environment's proxy settings with `None`. The URL is
+/// validated before it is saved.
+#[tauri::command]
+fn set_proxy_url(app: tauri::AppHandle, proxy_url: Option<String>) -> Result<(), AppError> {
+    let proxy_url = proxy_url.filter(|url| !url.trim().is_empty());
+    // Proxy URLs may carry credentials, which shouldn't end up in the log
+    let redacted = proxy_url
+        .as_deref()
+        .map(|url| match reqwest::Url::parse(url) {
+            Ok(mut parsed) => {
+                let _ = parsed.set_username("");
+                let _ = parsed.set_password(None);
+                parsed.to_string()
+            }
+            Err(_) => "<invalid>".to_string(),
+        });
+    info!("Setting proxy_url to: {:?}", redacted);
+    let client = build_http_client(proxy_url.as_deref())?;
+    write_setting(&app, "proxy_url", proxy_url).map_err(AppError::IoError)?;
+    *app.state::<HttpClient>().client.lock().unwrap() = client;
+    Ok(())
+}
+
+/// How long a 429 response asks to wait, from its Retry-After header in
+/// either seconds or HTTP-date form.
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    let value = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?;
+    if let Ok(secs) = value.trim().parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    (at.with_timezone(&chrono::Utc) - chrono::Utc::now())
+        .to_std()
+        .ok()
+}
+
+/// Sends `request`, retrying connection errors, timeouts, 5xx and 429
+/// responses with exponential backoff and jitter, up to the configured number
+/// of attempts. Other 4xx responses are returned immediately since repeating
+/// them won't help. After the last attempt the response is returned as-is,
+/// so callers still handle its status.
+async fn send_with_retry(
+    app: &tauri::AppHandle,
+    request: reqwest::RequestBuilder,
+) -> Result<reqwest::Response, reqwest::Error> {
+    let attempts = get_network_retry_attempts(app.clone()).max(1);
+    let mut attempt = 1;
+    loop {
+        // Requests with a streaming body can't be cloned, so they get one try
+        let Some(retry) = request.try_clone().filter(|_| attempt < attempts) else {
+            return request.send().await;
+        };
+        let server_delay = match retry.send().await {
+            Ok(response)
+                if response.status().is_server_error()
+                    || response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS =>
+            {
+                warn!(
+                    "{} answered {}, retrying (attempt {}/{})",
+                    response.url(),
+                    response.status(),
+                    attempt,
+                    attempts
+                );
+                retry_after(&response)
+            }
+            Ok(response) => return Ok(response),
+            Err(e) if e.is_connect() || e.is_timeout() || e.is_request() => {
+                warn!(
+                    "Request failed, retrying (attempt {}/{}): {}",
+                    attempt, attempts, e
+                );
+                None
+            }
+            Err(e) => return Err(e),
+        };
+
+        let delay = server_delay.unwrap_or_else(|| {
+            let backoff = RETRY_BASE_DELAY_MS << (attempt - 1).min(10);
+            // No rand dependency; the clock's sub-second part is random enough
+            // to spread out retries from several requests
+            let nanos = SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.subsec_nanos() as u64)
+                .unwrap_or(0);
+            Duration::from_millis(backoff + nanos % (backoff / 2 + 1))
+        });
+        tokio::time::sleep(delay.min(Duration::from_secs(MAX_RETRY_DELAY_SECS))).await;
+        attempt += 1;
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GridSource {
+    pub repo: String, // GitHub "owner/repo"
+    pub branch: String,
+}
+
+#[tauri::command]
+fn get_grid_source(app: tauri::AppHandle) -> GridSource {
+    GridSource {
+        repo: read_setting(&app, "grid_source_repo")
+            .unwrap_or_else(|| DEFAULT_GRID_REPO.to_string()),
+        branch: read_setting(&app, "grid_source_branch")
+            .unwrap_or_else(|| DEFAULT_GRID_BRANCH.to_string()),
+    }
+}
+
+/// Base URL of raw files in the grid source, with a trailing slash.
+fn raw_base_url(app: &tauri::AppHandle) -> String {
+    let source = get_grid_source(app.clone());
+    format!(
+        "https://raw.githubusercontent.com/{}/{}/",
+        source.repo, source.branch
+    )
+}
+
+/// GitHub contents API URL for `path` in the grid source's branch.
+fn github_contents_url(app: &tauri::AppHandle, path: &str) -> String {
+    let source = get_grid_source(app.clone());
+    format!(
+        "https://api.github.com/repos/{}/contents/{}?ref={}",
+        source.repo, path, source.branch
+    )
+}
+
+/// Points the app at another grid repository, e.g. a fork. The repo must
+/// have a `grids` directory on `branch`, so typos are caught here rather than
+/// on the next refresh. Cached data from the previous source is dropped.
+#[tauri::command]
+async fn set_grid_source(
+    app: tauri::AppHandle,
+    repo: String,
+    branch: String,
+) -> Result<(), AppError> {
+    info!("Setting grid source to: {}@{}", repo, branch);
+    let repo = repo.trim().trim_matches('/').to_string();
+    let branch = branch.trim().to_string();
+    let valid_repo = repo
+        .split_once('/')
+        .is_some_and(|(owner, name)| !owner.is_empty() && !name.is_empty() && !name.contains('/'));
+    if !valid_repo {
+        return Err(AppError::Other(format!(
+            "Repository must look like owner/repo, got {}",
+            repo
+        )));
+    }
+    if branch.is_empty() {
+        return Err(AppError::Other("Branch can't be empty".to_string()));
+    }
+
+    let url = format!(
+        "https://api.github.com/repos/{}/contents/grids?ref={}",
+        repo, branch
+    );
+    let token = github_token(&app);
+    let request = github_request(&http_client(&app), &url, token.as_deref());
+    let response = send_with_retry(&app, request)
+        .await
+        .map_err(|e| AppError::NetworkError(format!("Failed to reach GitHub: {}", e)))?;
+    match response.status() {
+        status if status.is_success() => {
+            let listing: Vec<GithubContent> = response.json().await.map_err(|_| {
+                AppError::Other(format!("grids in {}@{} is not a directory", repo, branch))
+            })?;
+            info!("{}@{} lists {} grid file(s)", repo, branch, listing.len());
+        }
+        reqwest::StatusCode::NOT_FOUND => {
+            return Err(AppError::Other(format!(
+                "{}@{} has no grids directory, or the repository or branch doesn't exist",
+                repo, branch
+            )))
+        }
+        reqwest::StatusCode::UNAUTHORIZED => {
+            return Err(AppError::Other(GITHUB_TOKEN_REJECTED.to_string()))
+        }
+        _ => return Err(github_status_error(&response, "validate the grid source")),
+    }
+
+    write_setting(&app, "grid_source_repo", repo).map_err(AppError::IoError)?;
+    write_setting(&app, "grid_source_branch", branch).map_err(AppError::IoError)?;
+    for path in [grid_list_cache_path(&app), source_info_cache_path(&app)]
+        .into_iter()
+        .flatten()
+    {
+        if path.exists() {
+            if let Err(e) = fs::remove_file(&path) {
+                warn!("Failed to remove {:?}: {}", path, e);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Resolves the cfg directory to operate on: an explicit path from the caller,
+/// then the user's override, then the cached result of auto-detection.
+fn resolve_config_dir(
+    app: &tauri::AppHandle,
+    dota_config_path: Option<String>,
+) -> Result<PathBuf, String> {
+    locate_config_dir(app, dota_config_path)?
+        .ok_or_else(|| "Could not find the Dota 2 config directory".to_string())
+}
+
+/// Like `resolve_config_dir`, but `None` when auto-detection finds nothing.
+/// A miss isn't cached so a later launch of Dota 2 is picked up.
+fn locate_config_dir(
+    app: &tauri::AppHandle,
+    dota_config_path: Option<String>,
+) -> Result<Option<PathBuf>, String> {
+    if let Some(path) = dota_config_path {
+        return Ok(Some(PathBuf::from(path)));
+    }
+    if let Some(path) = read_setting::<String>(app, "config_path_override") {
+        return Ok(Some(PathBuf::from(path)));
+    }
+
+    let cache = app.state::<DetectionCache>();
+    let mut cached = cache.config_path.lock().unwrap();
+    if let Some(path) = cached.as_ref() {
+        return Ok(Some(path.clone()));
+    }
+    let found = find_dota_config_path()?;
+    cached.clone_from(&found);
+    Ok(found)
+}
+
+/// Emits "grid-download-progress" for `grid_url`. Status is "downloading"
+/// while chunks arrive, then "done" or "failed" exactly once per fetch.
+fn emit_download_progress(
+    app: &tauri::AppHandle,
+    grid_url: &str,
+    downloaded: u64,
+    total: Option<u64>,
+    status: &str,
+    error: Option<String>,
+) {
+    let progress = DownloadProgress {
+        download_url: grid_url.to_string(),
+        downloaded,
+        total,
+        status: status.to_string(),
+        error,
+    };
+    if let Err(e) = app.emit("grid-download-progress", &progress) {
+        warn!("Failed to emit grid-download-progress: {}", e);
+    }
+}
+
+/// Downloads `download_url`, honoring the bandwidth limit and reporting
+/// progress under `grid_url` (the grid's canonical URL, even when a mirror
+/// serves it).
+async fn download_throttled(
+    app: &tauri::AppHandle,
+    download_url: &str,
+    grid_url: &str,
+) -> Result<Vec<u8>, String> {
+    let client = http_client(app);
+    let request = client
+        .get(download_url)
+        .header("User-Agent", "d2pt-grid-updater-app");
+    let mut response = send_with_retry(app, request)
+        .await
+        .map_err(|e| format!("Failed to download grid: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Failed to download grid: {}", response.status()));
+    }
+
+    // kilobits per second -> bytes per second; 0 means unlimited
+    let bytes_per_sec = get_bandwidth_limit_kbps(app.clone()) * 1000 / 8;
+    let total = response.content_length();
+    let started = Instant::now();
+    let mut content = Vec::new();
+    emit_download_progress(app, grid_url, 0, total, "downloading", None);
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Failed to read grid download: {}", e))?
+    {
+        content.extend_from_slice(&chunk);
+        emit_download_progress(
+            app,
+            grid_url,
+            content.len() as u64,
+            total,
+            "downloading",
+            None,
+        );
+        if bytes_per_sec > 0 {
+            let expected = Duration::from_secs_f64(content.len() as f64 / bytes_per_sec as f64);
+            let elapsed = started.elapsed();
+            if expected > elapsed {
+                tokio::time::sleep(expected - elapsed).await;
+            }
+        }
+    }
+    Ok(content)
+}
+
+/// Mirror URLs for a file in this repo, in the configured order, followed by
+/// the GitHub URL itself. URLs outside the repo are returned unchanged.
+fn source_candidates(app: &tauri::AppHandle, url: &str) -> Vec<String> {
+    let mut candidates: Vec<String> = match url.strip_prefix(&raw_base_url(app)) {
+        Some(relative_path) => get_mirror_urls(app.clone())
+            .iter()
+            .map(|base| format!("{}/{}", base.trim_end_matches('/'), relative_path))
+            .collect(),
+        None => Vec::new(),
+    };
+    candidates.push(url.to_string());
+    candidates
+}
+
+/// Refuses to install from URLs outside the grid source, its mirrors and the
+/// user's extra allowed sources, e.g. a stale cached list from another source.
+fn check_download_source(app: &tauri::AppHandle, download_url: &str) -> Result<(), String> {
+    let mut allowed = vec![raw_base_url(app)];
+    allowed.extend(get_mirror_urls(app.clone()));
+    allowed.extend(get_allowed_source_urls(app.clone()));
+    let is_allowed = allowed.iter().any(|base| {
+        let base = format!("{}/", base.trim_end_matches('/'));
+        download_url.starts_with(&base)
+    });
+    if is_allowed {
+        Ok(())
+    } else {
+        Err(format!(
+            "Refusing to install from {}: it is not the configured grid source or an allowed mirror",
+            download_url
+        ))
+    }
+}
+
+/// Downloads a grid from the first source that works, trying mirrors before
+/// GitHub. Repo grids are checked against grid_hashes.txt whatever the
+/// source, and a mirror serving a mismatching file is skipped. CDN mirrors
+/// cache aggressively, so a grid missing from the (possibly mirrored) hash
+/// list is looked up in GitHub's copy, and a grid GitHub doesn't list either
+/// is fetched from GitHub directly since mirrors can't be verified for it.
+async fn fetch_grid_bytes(app: &tauri::AppHandle, download_url: &str) -> Result<Vec<u8>, AppError> {
+    fetch_grid_bytes_verified(app, download_url)
+        .await
+        .map(|(content, _)| content)
+}
+
+/// Like fetch_grid_bytes, but also returns whether the content was checked
+/// against a published hash. Grids that aren't listed come back unverified.
+async fn fetch_grid_bytes_verified(
+    app: &tauri::AppHandle,
+    download_url: &str,
+) -> Result<(Vec<u8>, bool), AppError> {
+    let mut candidates = source_candidates(app, download_url);
+    let raw_base = raw_base_url(app);
+    let expected_hash = match download_url.strip_prefix(&raw_base) {
+        Some(relative_path) => {
+            let filename = relative_path.rsplit('/').next().unwrap_or(relative_path);
+            let listed = match download_grid_hashes(app.clone()).await {
+                Ok(grid_hashes) => grid_hashes.hashes.get(filename).cloned(),
+                Err(e) => {
+                    warn!("Could not load grid hashes for verification: {}", e);
+                    None
+                }
+            };
+            let expected = match listed {
+                Some(hash) => Some(hash),
+                None if candidates.len() > 1 => {
+                    warn!(
+                        "Hash list has no entry for {}, it may be stale; checking GitHub's copy",
+                        filename
+                    );
+                    fetch_hashes_from(app, &format!("{}grid_hashes.txt", raw_base), None)
+                        .await
+                        .ok()
+                        .and_then(|content| parse_grid_hashes(&content).hashes.remove(filename))
+                }
+                None => None,
+            };
+            if expected.is_none() && candidates.len() > 1 {
+                info!(
+                    "No published hash for {}, bypassing mirrors and using GitHub",
+                    filename
+                );
+                candidates = vec![download_url.to_string()];
+            }
+            expected
+        }
+        None => None,
+    };
+
+    let mut last_error = AppError::NetworkError(String::new());
+    for candidate in candidates {
+        let content = match download_throttled(app, &candidate, download_url).await {
+            Ok(content) => content,
+            Err(e) => {
+                warn!("Grid source {} failed: {}", candidate, e);
+                last_error = AppError::NetworkError(e);
+                continue;
+            }
+        };
+        if let Some(expected) = &expected_hash {
+            let actual = format!("{:x}", md5::compute(&content));
+            if &actual != expected {
+                warn!(
+                    "Grid source {} served a mismatching file (expected {}, got {}), \
+                     likely a stale cache; bypassing it",
+                    candidate, expected, actual
+                );
+                let filename = download_url.rsplit('/').next().unwrap_or(download_url);
+                last_error = AppError::HashMismatch(format!(
+                    "Hash mismatch for {}: expected {}, got {}",
+                    filename, expected, actual
+                ));
+                continue;
+            }
+        }
+        let size = content.len() as u64;
+        emit_download_progress(app, download_url, size, Some(size), "done", None);
+        return Ok((content, expected_hash.is_some()));
+    }
+    emit_download_progress(
+        app,
+        download_url,
+        0,
+        None,
+        "failed",
+        Some(last_error.to_string()),
+    );
+    Err(last_error)
+}
+
+/// Loads a grid from either an http(s) URL or a local file path.
+async fn load_grid_source(app: &tauri::AppHandle, source: &str) -> Result<Vec<u8>, AppError> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        fetch_grid_bytes(app, source).await
+    } else {
+        fs::read(source)
+            .map_err(|e| AppError::IoError(format!("Failed to read grid file {}: {}", source, e)))
+    }
+}
+
+/// Parses a grid, requiring a `configs` array whose pages have a
+/// `config_name` and `categories`; unknown fields are allowed. The error
+/// says whether the content was empty, an HTML page (e.g. an error page
+/// served with a 200), cut off, or valid JSON in the wrong shape.
+fn parse_grid_config(content: &[u8]) -> Result<HeroGridConfig, String> {
+    serde_json::from_slice(content).map_err(|e| {
+        let start = content.trim_ascii_start();
+        let reason = if start.is_empty() {
+            "the file is empty".to_string()
+        } else if start.starts_with(b"<") {
+            "got an HTML page instead of JSON".to_string()
+        } else {
+            match e.classify() {
+                serde_json::error::Category::Eof => format!("the file is truncated ({})", e),
+                serde_json::error::Category::Data => {
+                    format!("the JSON doesn't match the grid format ({})", e)
+                }
+                _ => e.to_string(),
+            }
+        };
+        format!("Invalid grid file: {}", reason)
+    })
+}
+
+fn required_config_fields(version: u64) -> &'static [&'static str] {
+    match version {
+        3 => &["config_name", "categories"],
+        _ => &[],
+    }
+}
+
+fn required_category_fields(version: u64) -> &'static [&'static str] {
+    match version {
+        3 => &[
+            "category_name",
+            "x_position",
+            "y_position",
+            "width",
+            "height",
+            "hero_ids",
+        ],
+        _ => &[],
+    }
+}
+
+/// Checks a parsed grid against the fields Dota requires for its version.
+fn validate_grid_value(value: &serde_json::Value) -> GridValidation {
+    let mut errors = Vec::new();
+    let version = value.get("version").and_then(|v| v.as_u64());
+
+    match value.get("version") {
+        None => errors.push("Missing \"version\" field".to_string()),
+        Some(_) if version.is_none() => errors.push("\"version\" is not a number".to_string()),
+        Some(_) => {}
+    }
+    if let Some(v) = version {
+        if !SUPPORTED_GRID_VERSIONS.contains(&v) {
+            errors.push(format!(
+                "Unsupported grid version {} (supported: {:?})",
+                v, SUPPORTED_GRID_VERSIONS
+            ));
+        }
+    }
+
+    let configs = value.get("configs").and_then(|c| c.as_array());
+    let config_count = configs.map_or(0, |c| c.len());
+    let supported_version = version.filter(|v| SUPPORTED_GRID_VERSIONS.contains(v));
+    match (configs, supported_version) {
+        (None, _) => errors.push("Missing \"configs\" array".to_string()),
+        (Some(configs), Some(version)) => {
+            for (i, config) in configs.iter().enumerate() {
+                for field in required_config_fields(version) {
+                    if config.get(field).is_none() {
+                        errors.push(format!("configs[{}] is missing \"{}\"", i, field));
+                    }
+                }
+                let categories = config.get("categories").and_then(|c| c.as_array());
+                for (j, category) in categories.into_iter().flatten().enumerate() {
+                    for field in required_category_fields(version) {
+                        if category.get(field).is_none() {
+                            errors.push(format!(
+                                "configs[{}].categories[{}] is missing \"{}\"",
+                                i, j, field
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        // Field requirements are only known for supported versions
+        (Some(_), None) => {}
+    }
+
+    GridValidation {
+        valid: errors.is_empty(),
+        version,
+        config_count,
+        errors,
+        hash: None,
+    }
+}
+
+/// Feeds a file to `consume` in 64 KiB chunks so large files aren't loaded
+/// whole.
+fn read_in_chunks(path: &Path, mut consume: impl FnMut(&[u8])) -> Result<(), String> {
+    use std::io::Read;
+
+    let mut file = fs::File::open(path).map_err(|e| format!("Failed to open {:?}: {}", path, e))?;
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+        if read == 0 {
+            return Ok(());
+        }
+        consume(&buffer[..read]);
+    }
+}
+
+/// MD5 of a file as lowercase hex, read in chunks so memory stays bounded
+/// however large the file is. Matches `format!("{:x}", md5::compute(..))`
+/// over the whole content.
+fn hash_file(path: &Path) -> Result<String, String> {
+    let mut context = md5::Context::new();
+    read_in_chunks(path, |chunk| context.consume(chunk))?;
+    Ok(format!("{:x}", context.compute()))
+}
+
+struct GridFileHashes {
+    raw: String,
+    normalized: String,
+}
+
+/// Hashes a grid file in one streaming pass, both as stored and normalized:
+/// UTF-8 BOM stripped, CRLF turned into LF and trailing whitespace trimmed.
+/// Editors and sync tools add these artifacts to otherwise identical grids,
+/// which would turn them "unknown". Published grids have none of them, so
+/// both hashes match the published one.
+fn hash_grid_file(path: &Path) -> Result<GridFileHashes, String> {
+    let mut raw = md5::Context::new();
+    let mut normalized = md5::Context::new();
+    let mut at_start = true;
+    let mut pending_cr = false;
+    // Whitespace seen since the last non-whitespace byte; only hashed once
+    // something follows it
+    let mut pending = Vec::new();
+    read_in_chunks(path, |chunk| {
+        raw.consume(chunk);
+        let mut chunk = chunk;
+        if at_start {
+            chunk = chunk.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(chunk);
+            at_start = false;
+        }
+
+        let mut lf_chunk = Vec::with_capacity(chunk.len());
+        for &byte in chunk {
+            if pending_cr && byte != b'\n' {
+                lf_chunk.push(b'\r');
+            }
+            pending_cr = byte == b'\r';
+            if !pending_cr {
+                lf_chunk.push(byte);
+            }
+        }
+
+        match lf_chunk.iter().rposition(|b| !b.is_ascii_whitespace()) {
+            Some(last) => {
+                normalized.consume(&pending);
+                normalized.consume(&lf_chunk[..=last]);
+                pending.clear();
+                pending.extend_from_slice(&lf_chunk[last + 1..]);
+            }
+            None => pending.extend_from_slice(&lf_chunk),
+        }
+    })?;
+    // A CR left at the end is trailing whitespace, so it's dropped
+    Ok(GridFileHashes {
+        raw: format!("{:x}", raw.compute()),
+        normalized: format!("{:x}", normalized.compute()),
+    })
+}
+
+/// Returns hero id -> localized name, preferring a fresh OpenDota fetch and
+/// falling back to the last cached copy. Empty when neither is available.
+async fn load_hero_names(app: &tauri::AppHandle) -> HashMap<u32, String> {
+    let cache_path = app
+        .path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join("heroes.json"));
+
+    let fetched: Result<Vec<OpenDotaHero>, String> = async {
+        let request = http_client(app)
+            .get("https://api.opendota.com/api/heroes")
+            .header("User-Agent", "d2pt-grid-updater-app");
+        let response = send_with_retry(app, request)
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("Failed to fetch heroes: {}", response.status()));
+        }
+        response.json().await.map_err(|e| e.to_string())
+    }
+    .await;
+
+    let heroes: HashMap<u32, String> = match fetched {
+        Ok(heroes) => heroes
+            .into_iter()
+            .map(|hero| (hero.id, hero.localized_name))
+            .collect(),
+        Err(e) => {
+            warn!("Could not fetch hero metadata ({}), using cache", e);
+            return cache_path
+                .and_then(|path| fs::read(path).ok())
+                .and_then(|content| serde_json::from_slice(&content).ok())
+                .unwrap_or_default();
+        }
+    };
+
+    if let Some(path) = cache_path {
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        if let Ok(content) = serde_json::to_vec(&heroes) {
+            let _ = fs::write(path, content);
+        }
+    }
+    heroes
+}
+
+struct MergeOutcome {
+    config: HeroGridConfig,
+    added: Vec<String>,
+    replaced: Vec<String>,
+}
+
+/// Merges `incoming` into `existing` by config_name: pages with a matching
+/// name are replaced in place, new ones are appended and everything else in
+/// `existing` is kept. Duplicate names in `incoming` collapse to the last one.
+fn merge_grid_configs(existing: &HeroGridConfig, incoming: &HeroGridConfig) -> MergeOutcome {
+    let mut config = existing.clone();
+    let mut added = Vec::new();
+    let mut replaced = Vec::new();
+
+    for page in &incoming.configs {
+        match config
+            .configs
+            .iter_mut()
+            .find(|existing_page| existing_page.config_name == page.config_name)
+        {
+            Some(existing_page) => {
+                *existing_page = page.clone();
+                if !replaced.contains(&page.config_name) && !added.contains(&page.config_name) {
+                    replaced.push(page.config_name.clone());
+                }
+            }
+            None => {
+                config.configs.push(page.clone());
+                added.push(page.config_name.clone());
+            }
+        }
+    }
+    if config.version.is_none() {
+        config.version = incoming.version;
+    }
+
+    MergeOutcome {
+        config,
+        added,
+        replaced,
+    }
+}
+
+/// Sorts configs and categories by name and heroes by id, so diffs between
+/// grids show content changes rather than ordering noise.
+fn normalize_grid_config(mut grid: HeroGridConfig) -> HeroGridConfig {
+    grid.configs
+        .sort_by(|a, b| a.config_name.cmp(&b.config_name));
+    for page in &mut grid.configs {
+        page.categories
+            .sort_by(|a, b| a.category_name.cmp(&b.category_name));
+        for category in &mut page.categories {
+            category.hero_ids.sort_unstable();
+        }
+    }
+    grid
+}
+
+fn summarize_configs(grid: &HeroGridConfig) -> Vec<ConfigSummary> {
+    grid.configs
+        .iter()
+        .map(|config| ConfigSummary {
+            config_name: config.config_name.clone(),
+            category_count: config.categories.len(),
+            hero_count: config
+                .categories
+                .iter()
+                .map(|category| category.hero_ids.len())
+                .sum(),
+        })
+        .collect()
+}
+
+/// Finds the page in `grid` corresponding to `config_name`: an exact match,
+/// or else one with the same label after the last " - ". d2pt page names embed
+/// the patch ("Dota2ProTracker 7.41c - Carry"), so only the label is stable.
+fn find_matching_page<'a>(
+    grid: &'a HeroGridConfig,
+    config_name: &str,
+) -> Option<&'a GridConfigPage> {
+    let label = |name: &str| name.rsplit(" - ").next().unwrap_or(name).to_string();
+    grid.configs
+        .iter()
+        .find(|page| page.config_name == config_name)
+        .or_else(|| {
+            let wanted = label(config_name);
+            grid.configs
+                .iter()
+                .find(|page| label(&page.config_name) == wanted)
+        })
+}
+
+fn hero_categories(page: &GridConfigPage) -> HashMap<u32, &str> {
+    let mut categories = HashMap::new();
+    for category in &page.categories {
+        for hero_id in &category.hero_ids {
+            categories
+                .entry(*hero_id)
+                .or_insert(category.category_name.as_str());
+        }
+    }
+    categories
+}
+
+fn grid_hero_ids(grid: &HeroGridConfig) -> HashSet<u32> {
+    grid.configs
+        .iter()
+        .flat_map(|config| &config.categories)
+        .flat_map(|category| category.hero_ids.iter().copied())
+        .collect()
+}
+
+fn hero_display_name(heroes: &HashMap<u32, String>, hero_id: u32) -> String {
+    heroes
+        .get(&hero_id)
+        .cloned()
+        .unwrap_or_else(|| format!("#{}", hero_id))
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Hashes the installed grid and looks it up in the published hash list.
+/// Grids that aren't in the list are reported as "custom".
+async fn identify_installed_grid(
+    app: &tauri::AppHandle,
+    config_dir: &Path,
+) -> Result<Option<DetectedGrid>, AppError> {
+    if !config_dir.join(GRID_FILE_NAME).exists() {
+        return Ok(None);
+    }
+    let grid_hashes = download_grid_hashes(app.clone()).await?;
+    identify_grid_with_hashes(config_dir, &grid_hashes).map_err(AppError::IoError)
+}
+
+fn identify_grid_with_hashes(
+    config_dir: &Path,
+    grid_hashes: &GridHashes,
+) -> Result<Option<DetectedGrid>, String> {
+    let grid_file_path = config_dir.join(GRID_FILE_NAME);
+    if !grid_file_path.exists() {
+        return Ok(None);
+    }
+    let hashes = hash_grid_file(&grid_file_path)?;
+
+    let matched = find_grid_by_hash(&hashes.raw, grid_hashes)
+        .map(|grid| (grid, "raw"))
+        .or_else(|| {
+            find_grid_by_hash(&hashes.normalized, grid_hashes).map(|grid| (grid, "normalized"))
+        });
+    let detected = match matched {
+        Some((grid, form)) => DetectedGrid {
+            raw_hash: Some(hashes.raw),
+            normalized_hash: Some(hashes.normalized),
+            matched_form: Some(form.to_string()),
+            ..grid
+        },
+        None => DetectedGrid {
+            grid_type: "custom".to_string(),
+            name: "Current Grid".to_string(),
+            date: "Unknown".to_string(),
+            parsed_date: None,
+            hash: hashes.normalized.clone(),
+            raw_hash: Some(hashes.raw),
+            normalized_hash: Some(hashes.normalized),
+            matched_form: None,
+            is_known: false,
+        },
+    };
+    Ok(Some(detected))
+}
+
+fn newest_grid_of_type<'a>(grids: &'a [Grid], grid_type: &str) -> Option<&'a Grid> {
+    grids
+        .iter()
+        .filter(|grid| classify_grid_type(&grid.name) == grid_type)
+        .max_by_key(|grid| grid.parsed_date)
+}
+
+/// Remembers the hash of the grid the app last wrote into `config_dir`, so
+/// later checks can tell an app-installed grid from one the user changed.
+/// Keyed by cfg directory so accounts sharing the PC don't overwrite it.
+fn record_activation(app: &tauri::AppHandle, config_dir: &Path, hash: &str) {
+    let mut hashes: HashMap<String, String> =
+        read_setting(app, "last_activated_hashes").unwrap_or_default();
+    hashes.insert(config_dir.to_string_lossy().to_string(), hash.to_string());
+    if let Err(e) = write_setting(app, "last_activated_hashes", hashes) {
+        warn!("Failed to record last activated hash: {}", e);
+    }
+}
+
+/// The hash recorded by `record_activation` for `config_dir`. Falls back to
+/// the single hash older versions stored for whichever directory was used.
+fn last_activated_hash(app: &tauri::AppHandle, config_dir: &Path) -> Option<String> {
+    read_setting::<HashMap<String, String>>(app, "last_activated_hashes")
+        .and_then(|mut hashes| hashes.remove(config_dir.to_string_lossy().as_ref()))
+        .or_else(|| read_setting(app, "last_activated_hash"))
+}
+
+fn managed_grids_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("managed_grids.json"))
+        .map_err(|e| format!("Could not get app data directory: {}", e))
+}
+
+fn read_managed_grids(app: &tauri::AppHandle) -> HashMap<String, ManagedGridMarker> {
+    managed_grids_path(app)
+        .ok()
+        .and_then(|path| fs::read(path).ok())
+        .and_then(|content| serde_json::from_slice(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_managed_grids(
+    app: &tauri::AppHandle,
+    markers: &HashMap<String, ManagedGridMarker>,
+) -> Result<(), String> {
+    let path = managed_grids_path(app)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_vec_pretty(markers).map_err(|e| e.to_string())?;
+    write_file_atomic(&path, &content)
+}
+
+/// Records that the app wrote the grid in `config_dir`, unless marking is
+/// turned off.
+fn stamp_managed_marker(app: &tauri::AppHandle, config_dir: &Path, download_url: &str, hash: &str) {
+    if !get_mark_managed_grids(app.clone()) {
+        return;
+    }
+    let mut markers = read_managed_grids(app);
+    markers.insert(
+        config_dir.to_string_lossy().to_string(),
+        ManagedGridMarker {
+            hash: hash.to_string(),
+            grid_name: download_url
+                .rsplit('/')
+                .next()
+                .unwrap_or(download_url)
+                .to_string(),
+            stamped_at: unix_now(),
+        },
+    );
+    if let Err(e) = write_managed_grids(app, &markers) {
+        warn!("Failed to record managed grid marker: {}", e);
+    }
+}
+
+/// Whether the installed grid is exactly the one the app last wrote there.
+/// Any edit, or a grid imported by hand, makes this false.
+#[tauri::command]
+fn is_app_managed_grid(
+    app: tauri::AppHandle,
+    dota_config_path: Option<String>,
+) -> Result<bool, AppError> {
+    let config_dir =
+        resolve_config_dir(&app, dota_config_path).map_err(AppError::ConfigNotFound)?;
+    let Some(marker) = read_managed_grids(&app).remove(config_dir.to_string_lossy().as_ref())
+    else {
+        return Ok(false);
+    };
+    let grid_file_path = config_dir.join(GRID_FILE_NAME);
+    Ok(grid_file_path.exists()
+        && hash_file(&grid_file_path).map_err(AppError::IoError)? == marker.hash)
+}
+
+/// Forgets the marker for the cfg directory, so the grid there is treated
+/// as not app-managed.
+#[tauri::command]
+fn clear_app_managed_marker(
+    app: tauri::AppHandle,
+    dota_config_path: Option<String>,
+) -> Result<(), AppError> {
+    let config_dir =
+        resolve_config_dir(&app, dota_config_path).map_err(AppError::ConfigNotFound)?;
+    let mut markers = read_managed_grids(&app);
+    if markers
+        .remove(config_dir.to_string_lossy().as_ref())
+        .is_some()
+    {
+        write_managed_grids(&app, &markers).map_err(AppError::IoError)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn get_mark_managed_grids(app: tauri::AppHandle) -> bool {
+    read_setting(&app, "mark_managed_grids").unwrap_or(true)
+}
+
+#[tauri::command]
+fn set_mark_managed_grids(app: tauri::AppHandle, enabled: bool) -> Result<(), AppError> {
+    info!("Setting mark_managed_grids to: {}", enabled);
+    write_setting(&app, "mark_managed_grids", enabled).map_err(AppError::IoError)
+}
+
+fn history_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("activation_history.json"))
+        .map_err(|e| format!("Could not get app data directory: {}", e))
+}
+
+fn read_history(app: &tauri::AppHandle) -> Vec<HistoryEntry> {
+    history_path(app)
+        .ok()
+        .and_then(|path| fs::read(path).ok())
+        .and_then(|content| serde_json::from_slice(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Appends an activation to the history, keeping the newest
+/// MAX_HISTORY_ENTRIES entries.
+fn record_history(app: &tauri::AppHandle, download_url: &str, hash: &str) {
+    let grid_name = download_url.rsplit('/').next().unwrap_or(download_url);
+    let mut history = read_history(app);
+    history.push(HistoryEntry {
+        timestamp: unix_now(),
+        grid_name: grid_name.to_string(),
+        grid_type: classify_grid_type(grid_name).to_string(),
+        date: parse_grid_date(grid_name),
+        hash: hash.to_string(),
+    });
+    if history.len() > MAX_HISTORY_ENTRIES {
+        history.drain(..history.len() - MAX_HISTORY_ENTRIES);
+    }
+
+    let result = history_path(app).and_then(|path| {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let content = serde_json::to_vec_pretty(&history).map_err(|e| e.to_string())?;
+        write_file_atomic(&path, &content)
+    });
+    if let Err(e) = result {
+        warn!("Failed to record activation history: {}", e);
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Writes the activation history to `dest` as CSV and returns the number of
+/// rows written (excluding the header).
+#[tauri::command]
+fn export_history_csv(app: tauri::AppHandle, dest: String) -> Result<usize, AppError> {
+    let history = read_history(&app);
+    let mut csv = String::from("timestamp,grid_name,grid_type,date,hash\n");
+    for entry in &history {
+        let fields = [
+            entry.timestamp.to_string(),
+            csv_field(&entry.grid_name),
+            csv_field(&entry.grid_type),
+            csv_field(&entry.date),
+            csv_field(&entry.hash),
+        ];
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    fs::write(&dest, csv)
+        .map_err(|e| AppError::IoError(format!("Failed to write {}: {}", dest, e)))?;
+    Ok(history.len())
+}
+
+fn remember_grid_type(app: &tauri::AppHandle, download_url: &str) {
+    let grid_type = classify_grid_type(download_url);
+    if grid_type != "unknown" {
+        let _ = write_setting(app, "last_grid_type", grid_type);
+    }
+}
+
+/// The grid type the user last activated, defaulting to d2pt.
+#[tauri::command]
+fn get_remembered_grid_type(app: tauri::AppHandle) -> String {
+    read_setting(&app, "last_grid_type").unwrap_or_else(|| "d2pt".to_string())
+}
+
+async fn run_update_check(
+    app: &tauri::AppHandle,
+    config_dir: &Path,
+) -> Result<UpdateCheckResult, AppError> {
+    let current = identify_installed_grid(app, config_dir).await?;
+    let grids = fetch_remote_grids(app).await?;
+
+    // Update the type the user last activated. Without one, stay on the
+    // installed grid's type, then fall back to d2pt.
+    let grid_type = read_setting::<String>(app, "last_grid_type")
+        .or_else(|| {
+            current
+                .as_ref()
+                .filter(|grid| grid.is_known)
+                .map(|grid| grid.grid_type.clone())
+        })
+        .unwrap_or_else(|| "d2pt".to_string());
+    let latest = newest_grid_of_type(&grids, &grid_type).cloned();
+    let update_available = match (&current, &latest) {
+        (Some(current), Some(latest)) if current.is_known => {
+            current.grid_type != classify_grid_type(&latest.name)
+                || latest.parsed_date > current.parsed_date
+        }
+        (None, Some(_)) => true,
+        _ => false,
+    };
+    // Best effort: a failed skew check shouldn't fail the update check
+    let clock_skew_warning = measure_clock_skew(app)
+        .await
+        .ok()
+        .and_then(|skew| skew.warning);
+
+    Ok(UpdateCheckResult {
+        current,
+        latest,
+        update_available,
+        clock_skew_warning,
+        skipped_reason: None,
+    })
+}
+
+/// Compares the local clock with the Date header of a GitHub response.
+async fn measure_clock_skew(app: &tauri::AppHandle) -> Result<ClockSkew, AppError> {
+    let request = http_client(app)
+        .head("https://api.github.com")
+        .header("User-Agent", "d2pt-grid-updater-app");
+    let response = send_with_retry(app, request)
+        .await
+        .map_err(|e| AppError::NetworkError(format!("Failed to reach GitHub: {}", e)))?;
+    let local_now = chrono::Utc::now();
+    let date_header = response
+        .headers()
+        .get(reqwest::header::DATE)
+        .and_then(|value| value.to_str().ok())
+        .ok_or_else(|| AppError::NetworkError("GitHub response has no Date header".to_string()))?;
+    let server_now = chrono::DateTime::parse_from_rfc2822(date_header).map_err(|e| {
+        AppError::NetworkError(format!("Invalid Date header {:?}: {}", date_header, e))
+    })?;
+
+    let skew_seconds = (local_now - server_now.with_timezone(&chrono::Utc)).num_seconds();
+    let exceeds_threshold = skew_seconds.abs() > CLOCK_SKEW_THRESHOLD_SECS;
+    Ok(ClockSkew {
+        skew_seconds,
+        exceeds_threshold,
+        warning: exceeds_threshold.then(|| {
+            format!(
+                "Your system clock is off by about {} minutes, so update checks may report wrong results. Please correct your date and time settings.",
+                skew_seconds.abs() / 60
+            )
+        }),
+    })
+}
+
+/// Installs the newest grid of the remembered type into the resolved config.
+async fn update_to_latest(app: &tauri::AppHandle) -> Result<Grid, String> {
+    let config_dir = resolve_config_dir(app, None)?;
+    let grid_type = get_remembered_grid_type(app.clone());
+    let grids = fetch_remote_grids(app).await?;
+    let latest = newest_grid_of_type(&grids, &grid_type)
+        .cloned()
+        .ok_or_else(|| format!("No published {} grid found", grid_type))?;
+    install_grid_with_retry(app, &config_dir, &latest.download_url).await?;
+    Ok(latest)
+}
+
+/// Records a scheduler tick for get_auto_update_status. An "updated" tick
+/// installed `latest`, so that grid is current and no update is left. A
+/// "skipped" tick keeps the grids and `last_checked` of the last real check.
+fn record_auto_update_run(
+    app: &tauri::AppHandle,
+    outcome: &str,
+    result: Option<&UpdateCheckResult>,
+    error: Option<String>,
+) {
+    let now = unix_now();
+    let skipped_reason = result.and_then(|r| r.skipped_reason.clone());
+    let status = if outcome == "skipped" {
+        let previous = read_setting::<AutoUpdateStatus>(app, "auto_update_status");
+        AutoUpdateStatus {
+            last_run: now,
+            outcome: outcome.to_string(),
+            last_checked: previous
+                .as_ref()
+                .and_then(|status| status.last_checked.or(Some(status.last_run))),
+            update_available: previous.as_ref().is_some_and(|s| s.update_available),
+            current_grid: previous.as_ref().and_then(|s| s.current_grid.clone()),
+            latest_grid: previous.and_then(|s| s.latest_grid),
+            error,
+            skipped_reason,
+            power_state: None,
+        }
+    } else {
+        let latest_grid = result.and_then(|r| r.latest.as_ref().map(|g| g.name.clone()));
+        let updated = outcome == "updated";
+        AutoUpdateStatus {
+            last_run: now,
+            outcome: outcome.to_string(),
+            last_checked: Some(now),
+            update_available: !updated && result.is_some_and(|r| r.update_available),
+            current_grid: if updated {
+                latest_grid.clone()
+            } else {
+                result.and_then(|r| r.current.as_ref().map(|g| g.name.clone()))
+            },
+            latest_grid,
+            error,
+            skipped_reason,
+            power_state: None,
+        }
+    };
+    if let Err(e) = write_setting(app, "auto_update_status", status) {
+        warn!("Failed to record auto-update status: {}", e);
+    }
+}
+
+/// Writes `content` next to `path` first and renames it into place, so Dota
+/// never sees a half-written grid file.
+fn write_file_atomic(path: &Path, content: &[u8]) -> Result<(), String> {
+    write_file_atomic_with_strategy(path, content).map(|_| ())
+}
+
+/// Like write_file_atomic, but returns how the file was put in place:
+/// "rename" normally, or "copy" when the temp file and the target turn out
+/// to be on different filesystems (e.g. a symlinked or bind-mounted target),
+/// where a rename fails with a cross-device error. The copy is synced before
+/// returning but isn't atomic.
+fn write_file_atomic_with_strategy(path: &Path, content: &[u8]) -> Result<&'static str, String> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| format!("Invalid target path: {:?}", path))?;
+    let mut tmp_name = file_name.to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let write_result = (|| -> std::io::Result<&'static str> {
+        let mut file = fs::File::create(&tmp_path)?;
+        std::io::Write::write_all(&mut file, content)?;
+        file.sync_all()?;
+        if same_filesystem(&tmp_path, path) {
+            match fs::rename(&tmp_path, path) {
+                Ok(()) => return Ok("rename"),
+                Err(e) if !is_cross_device_error(&e) => return Err(e),
+                Err(_) => info!("Rename into {:?} crossed devices, copying instead", path),
+            }
+        }
+        fs::copy(&tmp_path, path)?;
+        fs::OpenOptions::new().write(true).open(path)?.sync_all()?;
+        fs::remove_file(&tmp_path)?;
+        Ok("copy")
+    })();
+
+    write_result.map_err(|e| {
+        let _ = fs::remove_file(&tmp_path);
+        format!("Failed to write {:?}: {}", path, e)
+    })
+}
+
+/// Whether `a` and `b` (or b's directory, if b doesn't exist yet) live on the
+/// same filesystem. Only checked on Unix; elsewhere a cross-device rename
+/// error triggers the copy fallback instead.
+#[cfg(unix)]
+fn same_filesystem(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    let device = |path: &Path| {
+        fs::metadata(path)
+            .or_else(|_| fs::metadata(path.parent().unwrap_or(path)))
+            .map(|metadata| metadata.dev())
+            .ok()
+    };
+    match (device(a), device(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => true,
+    }
+}
+
+#[cfg(not(unix))]
+fn same_filesystem(_a: &Path, _b: &Path) -> bool {
+    true
+}
+
+fn is_cross_device_error(e: &std::io::Error) -> bool {
+    // EXDEV on Unix, ERROR_NOT_SAME_DEVICE on Windows
+    #[cfg(unix)]
+    let code = 18;
+    #[cfg(not(unix))]
+    let code = 17;
+    e.raw_os_error() == Some(code)
+}
+
+/// Reports how a grid write to the cfg directory would be done: "rename"
+/// (atomic) or "copy" when the target is on another filesystem.
+#[tauri::command]
+fn check_write_strategy(
+    app: tauri::AppHandle,
+    dota_config_path: Option<String>,
+) -> Result<String, AppError> {
+    let config_dir =
+        resolve_config_dir(&app, dota_config_path).map_err(AppError::ConfigNotFound)?;
+    let strategy = if same_filesystem(&config_dir, &config_dir.join(GRID_FILE_NAME)) {
+        "rename"
+    } else {
+        "copy"
+    };
+    Ok(strategy.to_string())
+}
+
+fn find_steam_path() -> Result<Option<PathBuf>, String> {
+    let mut steam_path: Option<PathBuf> = None;
+
+    #[cfg(target_os = "windows")]
+    {
+        if let Ok(hklm) = RegKey::predef(HKEY_LOCAL_MACHINE).open_subkey_with_flags(
+            "SOFTWARE\\Wow6432Node\\Valve\\Steam",
+            winreg::enums::KEY_READ,
+        ) {
+            if let Ok(path_str) = hklm.get_value::<String, _>("InstallPath") {
+                steam_path = Some(PathBuf::from(path_str));
+            }
+        }
+        if steam_path.is_none() {
+            if let Ok(hkcu) = RegKey::predef(HKEY_CURRENT_USER)
+                .open_subkey_with_flags("Software\\Valve\\Steam", winreg::enums::KEY_READ)
+            {
+                if let Ok(path_str) = hkcu.get_value::<String, _>("SteamPath") {
+                    steam_path = Some(PathBuf::from(path_str));
+                } else if let Ok(path_str) = hkcu.get_value::<String, _>("InstallPath") {
+                    steam_path = Some(PathBuf::from(path_str));
+                }
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let home_dir = dirs::home_dir().ok_or("Could not find home directory")?;
+        let possible_steam_paths = vec![
+            home_dir.join(".steam").join("steam"),
+            home_dir.join(".local").join("share").join("Steam"),
+            home_dir
+                .join("Library")
+                .join("Application Support")
+                .join("Steam"),
+            // Flatpak
+            home_dir
+                .join(".var")
+                .join("app")
+                .join("com.valvesoftware.Steam")
+                .join(".local")
+                .join("share")
+                .join("Steam"),
+            // Snap
+            home_dir
+                .join("snap")
+                .join("steam")
+                .join("common")
+                .join(".local")
+                .join("share")
+                .join("Steam"),
+        ];
+        // Prefer an install with both Dota and profiles, then one with
+        // either, so a leftover empty directory doesn't win
+        let score = |p: &Path| {
+            let has_dota =
+                find_dir_case_insensitive(p, &["steamapps", "common", "dota 2 beta"]).is_some();
+            let has_userdata = find_dir_case_insensitive(p, &["userdata"]).is_some();
+            has_dota as u8 + has_userdata as u8
+        };
+        if let Some((_, p)) = possible_steam_paths
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.is_dir())
+            .max_by_key(|(index, p)| (score(p), std::cmp::Reverse(*index)))
+        {
+            steam_path = Some(p.clone());
+        }
+    }
+
+    Ok(steam_path)
+}
+
+/// Finds a child directory whose name matches `name` ignoring case. Only
+/// needed on Linux, where the filesystem is case-sensitive and installs may
+/// use e.g. "Dota 2 beta" instead of the usual "dota 2 beta".
+#[cfg(target_os = "linux")]
+fn find_child_dir_ignore_case(parent: &Path, name: &str) -> Option<PathBuf> {
+    fs::read_dir(parent).ok()?.find_map(|entry| {
+        let entry = entry.ok()?;
+        let path = entry.path();
+        let matches = entry
+            .file_name()
+            .to_string_lossy()
+            .eq_ignore_ascii_case(name);
+        (matches && path.is_dir()).then_some(path)
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn find_child_dir_ignore_case(_parent: &Path, _name: &str) -> Option<PathBuf> {
+    None
+}
+
+/// Resolves `components` under `root`, preferring an exact match for each
+/// component and falling back to a case-insensitive one.
+fn find_dir_case_insensitive(root: &Path, components: &[&str]) -> Option<PathBuf> {
+    let mut current = root.to_path_buf();
+    for component in components {
+        let exact = current.join(component);
+        current = if exact.is_dir() {
+            exact
+        } else {
+            find_child_dir_ignore_case(&current, component)?
+        };
+    }
+    Some(current)
+}
+
+/// Library folders listed in <steam>/steamapps/libraryfolders.vdf. Handles
+/// both the old flat format (`"1" "D:\\SteamLibrary"`) and the newer one
+/// with a `"path"` key inside each numbered section.
+fn steam_library_paths(steam_path: &Path) -> Vec<PathBuf> {
+    let Some(content) = find_dir_case_insensitive(steam_path, &["steamapps"])
+        .and_then(|steamapps| fs::read_to_string(steamapps.join("libraryfolders.vdf")).ok())
+    else {
+        return Vec::new();
+    };
+
+    let tokens = vdf_tokens(&content);
+    let mut paths = Vec::new();
+    let mut depth = 0;
+    let mut i = 0;
+    while i < tokens.len() {
+        match tokens[i].as_str() {
+            "{" => depth += 1,
+            "}" => depth -= 1,
+            key => {
+                let value = tokens.get(i + 1).map(String::as_str).unwrap_or("{");
+                if value == "{" || value == "}" {
+                    i += 1;
+                    continue;
+                }
+                let old_format = depth == 1 && key.chars().all(|c| c.is_ascii_digit());
+                if key.eq_ignore_ascii_case("path") || old_format {
+                    paths.push(PathBuf::from(value));
+                }
+                i += 2;
+                continue;
+            }
+        }
+        i += 1;
+    }
+    paths
+}
+
+/// The main Steam install followed by any extra library folders registered
+/// in it, without duplicates.
+fn steam_roots() -> Result<Vec<PathBuf>, String> {
+    let Some(steam_path) = find_steam_path()? else {
+        return Ok(Vec::new());
+    };
+    // The main install is usually listed too, possibly spelled differently
+    let canonical = |path: &Path| fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut seen = vec![canonical(&steam_path)];
+    let mut roots = vec![steam_path.clone()];
+    for library in steam_library_paths(&steam_path) {
+        if library.is_dir() && !seen.contains(&canonical(&library)) {
+            seen.push(canonical(&library));
+            roots.push(library);
+        }
+    }
+    Ok(roots)
+}
+
+fn count_userdata_profiles(steam_path: &Path) -> usize {
+    find_dir_case_insensitive(steam_path, &["userdata"])
+        .and_then(|userdata_path| fs::read_dir(userdata_path).ok())
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_dir())
+                .count()
+        })
+        .unwrap_or(0)
+}
+
+/// Returns the Steam user id and Dota 2 cfg directory of every profile that
+/// has one.
+fn list_profile_cfg_dirs() -> Result<Vec<(String, PathBuf)>, String> {
+    list_profile_cfg_dirs_for_app(DOTA_APP_ID)
+}
+
+/// Like list_profile_cfg_dirs, for another Steam app id such as a Dota test
+/// client. Every Steam root from steam_roots is scanned.
+fn list_profile_cfg_dirs_for_app(app_id: &str) -> Result<Vec<(String, PathBuf)>, String> {
+    let mut cfg_dirs = Vec::new();
+    for steam_path in steam_roots()? {
+        let userdata_path = match find_dir_case_insensitive(&steam_path, &["userdata"]) {
+            Some(p) => p,
+            None => continue,
+        };
+
+        for entry in fs::read_dir(&userdata_path).map_err(|e| e.to_string())? {
+            let entry = entry.map_err(|e| e.to_string())?;
+            let user_id_path = entry.path();
+            if user_id_path.is_dir() {
+                if let Some(dota_cfg_dir) =
+                    find_dir_case_insensitive(&user_id_path, &[app_id, "remote", "cfg"])
+                {
+                    let user_id = entry.file_name().to_string_lossy().to_string();
+                    cfg_dirs.push((user_id, dota_cfg_dir));
+                }
+            }
+        }
+    }
+    Ok(cfg_dirs)
+}
+
+#[tauri::command]
+fn get_extra_dota_app_ids(app: tauri::AppHandle) -> Vec<u32> {
+    read_setting(&app, "extra_dota_app_ids").unwrap_or_default()
+}
+
+/// Sets additional Steam app ids (e.g. 205790 for the Dota 2 test client)
+/// whose cfg directories list_dota_cfg_dirs should include. 570 is always
+/// included and stays the one auto-detection picks.
+#[tauri::command]
+fn set_extra_dota_app_ids(app: tauri::AppHandle, app_ids: Vec<u32>) -> Result<(), AppError> {
+    let mut app_ids: Vec<u32> = app_ids
+        .into_iter()
+        .filter(|id| id.to_string() != DOTA_APP_ID)
+        .collect();
+    app_ids.sort_unstable();
+    app_ids.dedup();
+    info!("Setting extra_dota_app_ids to: {:?}", app_ids);
+    write_setting(&app, "extra_dota_app_ids", app_ids).map_err(AppError::IoError)
+}
+
+/// Lists the cfg directories of every profile for 570 and any extra app ids,
+/// tagged with the app id each belongs to.
+#[tauri::command]
+fn list_dota_cfg_dirs(app: tauri::AppHandle) -> Result<Vec<CfgDirEntry>, AppError> {
+    let mut app_ids = vec![DOTA_APP_ID.to_string()];
+    app_ids.extend(get_extra_dota_app_ids(app).iter().map(|id| id.to_string()));
+
+    let mut entries = Vec::new();
+    for app_id in app_ids {
+        for (user_id, path) in list_profile_cfg_dirs_for_app(&app_id).map_err(AppError::IoError)? {
+            entries.push(CfgDirEntry {
+                user_id,
+                app_id: app_id.clone(),
+                path: path.to_string_lossy().to_string(),
+            });
+        }
+    }
+    Ok(entries)
+}
+
+/// The cfg directory the app will actually use: the override when set,
+/// otherwise the auto-detected one.
+#[tauri::command]
+fn get_dota_config_path(app: tauri::AppHandle) -> Result<Option<PathBuf>, AppError> {
+    locate_config_dir(&app, None).map_err(AppError::ConfigNotFound)
+}
+
+#[tauri::command]
+fn find_dota_config_path() -> Result<Option<PathBuf>, AppError> {
+    let mut latest_cfg_path: Option<PathBuf> = None;
+    let mut latest_mod_time: Option<SystemTime> = None;
+
+    for (_, dota_cfg_dir) in list_profile_cfg_dirs().map_err(AppError::IoError)? {
+        let hero_grid_path = dota_cfg_dir.join(GRID_FILE_NAME);
+        if hero_grid_path.exists() {
+            if let Ok(metadata) = fs::metadata(&hero_grid_path) {
+                if let Ok(mod_time) = metadata.modified() {
+                    if let Some(latest) = latest_mod_time {
+                        if mod_time.cmp(&latest) == Ordering::Greater {
+                            latest_mod_time = Some(mod_time);
+                            latest_cfg_path = Some(dota_cfg_dir.clone());
+                        }
+                    } else {
+                        latest_mod_time = Some(mod_time);
+                        latest_cfg_path = Some(dota_cfg_dir.clone());
+                    }
+                }
+            }
+        } else {
+            if latest_cfg_path.is_none() {
+                latest_cfg_path = Some(dota_cfg_dir);
+            }
+        }
+    }
+    Ok(latest_cfg_path)
+}
+
+/// Splits VDF/ACF text into tokens: quoted strings (unquoted) and braces.
+fn vdf_tokens(content: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = content.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' | '}' => tokens.push(c.to_string()),
+            '"' => {
+                let mut token = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => token.extend(chars.next()),
+                        '"' => break,
+                        _ => token.push(c),
+                    }
+                }
+                tokens.push(token);
+            }
+            _ => {}
+        }
+    }
+    tokens
+}
+
+/// Returns the value of the first `"key" "value"` pair named `key`, ignoring
+/// case and section nesting.
+fn vdf_value(content: &str, key: &str) -> Option<String> {
+    vdf_tokens(content)
+        .windows(2)
+        .find(|pair| {
+            pair[0].eq_ignore_ascii_case(key) && !matches!(pair[1].as_str(), "{" | "}" | "")
+        })
+        .map(|pair| pair[1].clone())
+}
+
+// <userdata>/<id>/570/remote/cfg -> <userdata>/<id>/config/localconfig.vdf
+fn profile_localconfig_path(cfg_dir: &Path) -> Option<PathBuf> {
+    cfg_dir
+        .ancestors()
+        .nth(3)
+        .map(|profile_dir| profile_dir.join("config").join("localconfig.vdf"))
+}
+
+/// Lists every Steam account with a Dota 2 cfg directory, most recently
+/// changed grid first. `active` marks the one the app uses, which is the
+/// config path override when one is set, and `duplicate` the ones
+/// find_duplicate_profiles groups together. Pass an account's config_path as
+/// dota_config_path to target it explicitly.
+#[tauri::command]
+fn list_dota_config_paths(app: tauri::AppHandle) -> Result<Vec<SteamAccount>, AppError> {
+    let active_dir = locate_config_dir(&app, None).map_err(AppError::ConfigNotFound)?;
+    let duplicates: HashSet<String> = find_duplicate_profiles()?.into_iter().flatten().collect();
+    let mut accounts: Vec<SteamAccount> = list_profile_cfg_dirs()
+        .map_err(AppError::IoError)?
+        .into_iter()
+        .map(|(user_id, cfg_dir)| SteamAccount {
+            persona_name: profile_localconfig_path(&cfg_dir)
+                .and_then(|path| fs::read_to_string(path).ok())
+                .and_then(|content| vdf_value(&content, "PersonaName")),
+            last_modified: fs::metadata(cfg_dir.join(GRID_FILE_NAME))
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|age| age.as_secs()),
+            active: active_dir.as_deref() == Some(cfg_dir.as_path()),
+            duplicate: duplicates.contains(cfg_dir.to_string_lossy().as_ref()),
+            config_path: cfg_dir.to_string_lossy().to_string(),
+            user_id,
+        })
+        .collect();
+    accounts.sort_by(|a, b| b.last_modified.cmp(&a.last_modified));
+    Ok(accounts)
+}
+
+// Offset between a SteamID64 and the 32-bit account id Steam names
+// userdata folders after
+const STEAM_ID64_BASE: u64 = 76561197960265728;
+
+/// The Steam account id a profile belongs to: the SteamID or AccountID saved
+/// in its localconfig.vdf, or else the userdata folder name, which Steam
+/// names after the account id. None for the shared "0" profile.
+fn profile_account_id(user_id: &str, cfg_dir: &Path) -> Option<u64> {
+    if user_id == "0" {
+        return None;
+    }
+    let content = profile_localconfig_path(cfg_dir).and_then(|path| fs::read_to_string(path).ok());
+    let from_vdf = content.as_deref().and_then(|content| {
+        ["SteamID", "AccountID"]
+            .iter()
+            .find_map(|key| vdf_value(content, key)?.parse::<u64>().ok())
+    });
+    from_vdf
+        .or_else(|| user_id.parse().ok())
+        .map(|id| id.checked_sub(STEAM_ID64_BASE).unwrap_or(id))
+}
+
+/// Groups the cfg directories of profiles belonging to the same Steam account,
+/// e.g. one account seen under two Steam installs or library roots. Returns
+/// only groups with more than one cfg directory. Read-only.
+#[tauri::command]
+fn find_duplicate_profiles() -> Result<Vec<Vec<String>>, AppError> {
+    let mut groups: HashMap<u64, Vec<String>> = HashMap::new();
+    for (user_id, cfg_dir) in list_profile_cfg_dirs().map_err(AppError::IoError)? {
+        if let Some(account_id) = profile_account_id(&user_id, &cfg_dir) {
+            groups
+                .entry(account_id)
+                .or_default()
+                .push(cfg_dir.to_string_lossy().to_string());
+        }
+    }
+
+    let mut duplicates: Vec<Vec<String>> = groups
+        .into_values()
+        .filter(|paths| paths.len() > 1)
+        .map(|mut paths| {
+            paths.sort();
+            paths
+        })
+        .collect();
+    duplicates.sort();
+    Ok(duplicates)
+}
+
+/// Returns Dota's language as the Steam language code (e.g. "english",
+/// "russian", "schinese"). Reads the app manifest first, then the profile's
+/// localconfig.vdf, and falls back to "english".
+#[tauri::command]
+fn get_dota_language(app: tauri::AppHandle, dota_config_path: Option<String>) -> String {
+    let mut candidates = Vec::new();
+    for steam_path in steam_roots().unwrap_or_default() {
+        if let Some(steamapps) = find_dir_case_insensitive(&steam_path, &["steamapps"]) {
+            candidates.push(steamapps.join("appmanifest_570.acf"));
+        }
+    }
+    if let Ok(config_dir) = resolve_config_dir(&app, dota_config_path) {
+        candidates.extend(profile_localconfig_path(&config_dir));
+    }
+
+    candidates
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .find_map(|content| vdf_value(&content, "language"))
+        .map(|language| language.to_lowercase())
+        .unwrap_or_else(|| "english".to_string())
+}
+
+async fn fetch_remote_grids(app: &tauri::AppHandle) -> Result<Vec<Grid>, AppError> {
+    let fetch = fetch_remote_grids_if_changed(app, None, None).await?;
+    fetch.grids.ok_or_else(|| {
+        AppError::NetworkError("GitHub answered 304 to an unconditional request".to_string())
+    })
+}
+
+struct RemoteGridFetch {
+    grids: Option<Vec<Grid>>, // None when GitHub answered 304 Not Modified
+    etag: Option<String>,
+}
+
+/// Turns a failed GitHub API response into an error, telling rate limiting
+/// (429, or 403 with no requests left) apart from other failures.
+fn github_status_error(response: &reqwest::Response, action: &str) -> AppError {
+    let status = response.status();
+    let header = |name: &str| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok())
+    };
+    let exhausted = header("x-ratelimit-remaining") == Some(0);
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS
+        || (status == reqwest::StatusCode::FORBIDDEN && exhausted)
+    {
+        let message = match header("x-ratelimit-reset") {
+            Some(reset) => format!(
+                "GitHub rate limit reached trying to {}; it resets in {} minute(s)",
+                action,
+                reset.saturating_sub(unix_now()).div_ceil(60)
+            ),
+            None => format!("GitHub rate limit reached trying to {}", action),
+        };
+        return AppError::RateLimited(message);
+    }
+    AppError::NetworkError(format!("Failed to {}: {}", action, status))
+}
+
+/// Fetches the grid listing, sending `etag` as If-None-Match so an unchanged
+/// listing costs a 304 instead of a full response.
+async fn fetch_remote_grids_if_changed(
+    app: &tauri::AppHandle,
+    etag: Option<&str>,
+    token: Option<&str>,
+) -> Result<RemoteGridFetch, AppError> {
+    let client = http_client(app);
+    let api_url = github_contents_url(app, "grids");
+    let mut request = client
+        .get(&api_url)
+        .header("User-Agent", "d2pt-grid-updater-app");
+    if let Some(etag) = etag {
+        request = request.header("If-None-Match", etag);
+    }
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    let response = send_with_retry(app, request)
+        .await
+        .map_err(|e| AppError::NetworkError(format!("Failed to reach GitHub: {}", e)))?;
+    if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+        return Ok(RemoteGridFetch {
+            grids: None,
+            etag: etag.map(str::to_string),
+        });
+    }
+    if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+        return Err(AppError::Other(GITHUB_TOKEN_REJECTED.to_string()));
+    }
+    if !response.status().is_success() {
+        return Err(github_status_error(&response, "fetch grids from GitHub"));
+    }
+    let etag = response
+        .headers()
+        .get(reqwest::header::ETAG)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let contents: Vec<GithubContent> = response
+        .json()
+        .await
+        .map_err(|e| AppError::NetworkError(format!("Invalid grid listing: {}", e)))?;
+    let mut grids: Vec<Grid> = contents
+        .into_iter()
+        .filter_map(|item| {
             if item.name.ends_with(".json") && item.download_url.is_some() {
-                let parts: Vec<&str> = item.name.split('_').collect();
-                let date = parts
-                    .iter()
-                    .find(|p| p.starts_with("20"))
-                    .map(|s| s.to_string())
-                    .unwrap_or_else(|| "Unknown".to_string());
                 Some(Grid {
-                    name: item.name.clone(),
-                    date,
+                    date: parse_grid_date(&item.name),
+                    parsed_date: parse_grid_naive_date(&item.name),
+                    patch: parse_grid_patch(&item.name),
+                    grid_type: classify_grid_type(&item.name).to_string(),
+                    name: item.name,
                     download_url: item.download_url.unwrap(),
                 })
-            } else {
-                None
+            } else {
+                None
+            }
+        })
+        .collect();
+    sort_grids_newest_first(&mut grids);
+    Ok(RemoteGridFetch {
+        grids: Some(grids),
+        etag,
+    })
+}
+
+fn grid_list_cache_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("grid_list_cache.json"))
+        .map_err(|e| format!("Could not get app data directory: {}", e))
+}
+
+// Same-day grids keep a stable order by name
+fn sort_grids_newest_first(grids: &mut [Grid]) {
+    grids.sort_by(|a, b| {
+        b.parsed_date
+            .cmp(&a.parsed_date)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+}
+
+fn read_grid_list_cache(app: &tauri::AppHandle) -> Option<GridListCache> {
+    let content = fs::read(grid_list_cache_path(app).ok()?).ok()?;
+    let mut cache: GridListCache = serde_json::from_slice(&content).ok()?;
+    // Caches written by older versions lack the derived fields and order
+    for grid in &mut cache.grids {
+        if grid.parsed_date.is_none() {
+            grid.parsed_date = parse_grid_naive_date(&grid.name);
+        }
+        if grid.grid_type.is_empty() {
+            grid.grid_type = classify_grid_type(&grid.name).to_string();
+        }
+    }
+    sort_grids_newest_first(&mut cache.grids);
+    Some(cache)
+}
+
+fn write_grid_list_cache(
+    app: &tauri::AppHandle,
+    grids: &[Grid],
+    etag: Option<String>,
+) -> Result<(), String> {
+    let path = grid_list_cache_path(app)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let cache = GridListCache {
+        fetched_at: unix_now(),
+        grids: grids.to_vec(),
+        etag,
+    };
+    let content = serde_json::to_vec(&cache).map_err(|e| e.to_string())?;
+    write_file_atomic(&path, &content)
+}
+
+/// Lists the published grids. A cached list younger than GRID_LIST_TTL_SECS
+/// is returned as is unless `force` is set, which keeps the unauthenticated
+/// GitHub API from rate limiting us. Past that, the cached ETag is sent so an
+/// unchanged list costs a 304 and reuses the cache. If GitHub fails, any
+/// cached list is returned instead, marked stale. With `grid_type`, only
+/// grids of that type are returned; an unknown type yields an empty list.
+#[tauri::command]
+async fn list_remote_grids(
+    app: tauri::AppHandle,
+    force: bool,
+    grid_type: Option<String>,
+) -> Result<RemoteGridList, AppError> {
+    let mut list = load_remote_grid_list(&app, force).await?;
+    if let Some(grid_type) = grid_type {
+        list.grids
+            .retain(|grid| classify_grid_type(&grid.name) == grid_type);
+    }
+    Ok(list)
+}
+
+async fn load_remote_grid_list(
+    app: &tauri::AppHandle,
+    force: bool,
+) -> Result<RemoteGridList, AppError> {
+    let cached = read_grid_list_cache(app);
+    if let Some(cache) = &cached {
+        if !force && unix_now().saturating_sub(cache.fetched_at) < GRID_LIST_TTL_SECS {
+            return Ok(RemoteGridList {
+                grids: cache.grids.clone(),
+                fetched_at: cache.fetched_at,
+                stale: false,
+            });
+        }
+    }
+
+    // Without cached grids to fall back on, a 304 would be useless
+    let etag = cached.as_ref().and_then(|cache| cache.etag.as_deref());
+    let token = github_token(app);
+    let (grids, etag) = match fetch_remote_grids_if_changed(app, etag, token.as_deref()).await {
+        Ok(RemoteGridFetch {
+            grids: Some(grids),
+            etag,
+        }) => (grids, etag),
+        Ok(RemoteGridFetch { grids: None, etag }) => {
+            info!("Grid list not modified, reusing the cache");
+            let grids = cached.map(|cache| cache.grids).unwrap_or_default();
+            (grids, etag)
+        }
+        Err(e) => {
+            warn!("Failed to fetch grid list: {}", e);
+            let cache = cached.ok_or(e)?;
+            return Ok(RemoteGridList {
+                grids: cache.grids,
+                fetched_at: cache.fetched_at,
+                stale: true,
+            });
+        }
+    };
+    if let Err(e) = write_grid_list_cache(app, &grids, etag) {
+        warn!("Failed to cache grid list: {}", e);
+    }
+    refresh_tray_grids(app, &grids);
+    Ok(RemoteGridList {
+        grids,
+        fetched_at: unix_now(),
+        stale: false,
+    })
+}
+
+/// The published grids keyed by type, newest first, keeping at most
+/// `per_type` grids of each type (all of them when None).
+#[tauri::command]
+async fn list_remote_grids_grouped(
+    app: tauri::AppHandle,
+    per_type: Option<usize>,
+) -> Result<HashMap<String, Vec<Grid>>, AppError> {
+    let list = load_remote_grid_list(&app, false).await?;
+    let mut grouped: HashMap<String, Vec<Grid>> = HashMap::new();
+    for grid in list.grids {
+        let grid_type = classify_grid_type(&grid.name).to_string();
+        let entries = grouped.entry(grid_type).or_default();
+        if per_type.is_none_or(|limit| entries.len() < limit) {
+            entries.push(grid);
+        }
+    }
+    Ok(grouped)
+}
+
+/// Downloads a hash list. `token` is sent as a bearer token and must only be
+/// passed for GitHub URLs.
+async fn fetch_hashes_from(
+    app: &tauri::AppHandle,
+    url: &str,
+    token: Option<&str>,
+) -> Result<String, String> {
+    let client = http_client(app);
+    let mut request = client
+        .get(url)
+        .header("User-Agent", "d2pt-grid-updater-app");
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    let response = send_with_retry(app, request)
+        .await
+        .map_err(|e| format!("Failed to download grid hashes: {}", e))?;
+
+    if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+        return Err(GITHUB_TOKEN_REJECTED.to_string());
+    }
+    if !response.status().is_success() {
+        return Err(format!(
+            "Failed to download grid hashes: {}",
+            response.status()
+        ));
+    }
+
+    response.text().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn download_grid_hashes(app: tauri::AppHandle) -> Result<GridHashes, AppError> {
+    let raw_base = raw_base_url(&app);
+    let url = format!("{}grid_hashes.txt", raw_base);
+
+    let mut content = None;
+    let mut last_error = String::new();
+    let token = github_token(&app);
+    for candidate in source_candidates(&app, &url) {
+        // Mirrors never get the token
+        let token = token
+            .as_deref()
+            .filter(|_| candidate.starts_with(&raw_base));
+        match fetch_hashes_from(&app, &candidate, token).await {
+            Ok(text) => {
+                content = Some(text);
+                break;
+            }
+            Err(e) => {
+                warn!("Hashes source {} failed: {}", candidate, e);
+                last_error = e;
+            }
+        }
+    }
+    let content = content.ok_or(AppError::NetworkError(last_error))?;
+    Ok(parse_grid_hashes(&content))
+}
+
+fn parse_grid_hashes(content: &str) -> GridHashes {
+    let mut hashes = HashMap::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some((filename, hash)) = line.split_once(',') {
+            hashes.insert(filename.to_string(), hash.to_string());
+        }
+    }
+
+    GridHashes { hashes }
+}
+
+/// Hashes the installed grid in both raw and normalized form and tries each
+/// against the published hash list. Pass `grid_hashes` when the caller
+/// already has the list to skip the download. A grid that isn't in the list
+/// is "custom"; if the list couldn't be loaded at all it's "unknown", but the
+/// hashes are still returned.
+#[tauri::command]
+async fn detect_current_grid(
+    app: tauri::AppHandle,
+    dota_config_path: Option<String>,
+    grid_hashes: Option<GridHashes>,
+) -> Result<Option<DetectedGrid>, AppError> {
+    let Some(config_path) =
+        locate_config_dir(&app, dota_config_path).map_err(AppError::ConfigNotFound)?
+    else {
+        return Ok(None);
+    };
+
+    let grid_file_path = config_path.join(GRID_FILE_NAME);
+    if !grid_file_path.exists() {
+        return Ok(None);
+    }
+
+    let (grid_hashes, lookup_failed) = match grid_hashes {
+        Some(grid_hashes) => (grid_hashes, false),
+        None => match download_grid_hashes(app).await {
+            Ok(grid_hashes) => (grid_hashes, false),
+            Err(e) => {
+                warn!("Could not load grid hashes for detection: {}", e);
+                let empty = GridHashes {
+                    hashes: HashMap::new(),
+                };
+                (empty, true)
+            }
+        },
+    };
+    let detected =
+        identify_grid_with_hashes(&config_path, &grid_hashes).map_err(AppError::IoError)?;
+    Ok(detected.map(|mut grid| {
+        if lookup_failed {
+            grid.grid_type = "unknown".to_string();
+        }
+        grid
+    }))
+}
+
+fn find_grid_by_hash(grid_hash: &str, grid_hashes: &GridHashes) -> Option<DetectedGrid> {
+    grid_hashes
+        .hashes
+        .iter()
+        .find(|(_, hash)| hash.as_str() == grid_hash)
+        .map(|(filename, hash)| DetectedGrid {
+            grid_type: classify_grid_type(filename).to_string(),
+            name: filename.clone(),
+            date: parse_grid_date(filename),
+            parsed_date: parse_grid_naive_date(filename),
+            hash: hash.clone(),
+            raw_hash: None,
+            normalized_hash: None,
+            matched_form: None,
+            is_known: true,
+        })
+}
+
+/// Identifies a grid by hash and, when found, also returns the newest
+/// published grid of the same type so the caller knows if it's outdated.
+#[tauri::command]
+async fn match_grid_hash(
+    app: tauri::AppHandle,
+    grid_hash: String,
+    grid_hashes: GridHashes,
+) -> Result<Option<GridMatch>, AppError> {
+    let Some(grid) = find_grid_by_hash(&grid_hash, &grid_hashes) else {
+        return Ok(None);
+    };
+    let newest = match fetch_remote_grids(&app).await {
+        Ok(grids) => newest_grid_of_type(&grids, &grid.grid_type).cloned(),
+        Err(e) => {
+            warn!("Could not look up the newest grid: {}", e);
+            None
+        }
+    };
+    let newer_available = newest
+        .as_ref()
+        .is_some_and(|newest| newest.parsed_date > grid.parsed_date);
+    Ok(Some(GridMatch {
+        grid,
+        newest,
+        newer_available,
+    }))
+}
+
+/// Explains why detection did or didn't find a config, so the UI can give a
+/// specific hint instead of a generic "not found".
+#[tauri::command]
+fn get_detection_status() -> Result<DetectionStatus, AppError> {
+    let status =
+        |status: &str, steam_path: Option<&Path>, config_path: Option<PathBuf>, message: &str| {
+            DetectionStatus {
+                status: status.to_string(),
+                steam_path: steam_path.map(|p| p.to_string_lossy().to_string()),
+                config_path: config_path.map(|p| p.to_string_lossy().to_string()),
+                message: message.to_string(),
+            }
+        };
+
+    let steam_path = match find_steam_path().map_err(AppError::IoError)? {
+        Some(p) => p,
+        None => {
+            return Ok(status(
+                "steam_not_found",
+                None,
+                None,
+                "Steam installation could not be found.",
+            ))
+        }
+    };
+
+    if find_dir_case_insensitive(&steam_path, &["userdata"]).is_none() {
+        return Ok(status(
+            "userdata_missing",
+            Some(&steam_path),
+            None,
+            "Steam was found but has no userdata folder. Log into Steam and launch Dota 2 once.",
+        ));
+    }
+
+    if count_userdata_profiles(&steam_path) == 0 {
+        return Ok(status(
+            "userdata_empty",
+            Some(&steam_path),
+            None,
+            "Steam was found but no user profiles exist yet. Launch Dota 2 once so Steam creates your profile.",
+        ));
+    }
+
+    match find_dota_config_path()? {
+        Some(config_path) => Ok(status(
+            "found",
+            Some(&steam_path),
+            Some(config_path),
+            "Dota 2 config directory found.",
+        )),
+        None => Ok(status(
+            "dota_cfg_missing",
+            Some(&steam_path),
+            None,
+            "Steam profiles exist but none has a Dota 2 config yet. Launch Dota 2 once.",
+        )),
+    }
+}
+
+#[tauri::command]
+fn get_profile_count() -> Result<ProfileCount, AppError> {
+    let total = find_steam_path()
+        .map_err(AppError::IoError)?
+        .map(|steam_path| count_userdata_profiles(&steam_path))
+        .unwrap_or(0);
+    // userdata/0 is Steam's anonymous profile, not a real account
+    let with_cfg = list_profile_cfg_dirs()
+        .map_err(AppError::IoError)?
+        .iter()
+        .filter(|(user_id, _)| user_id != "0")
+        .count();
+    Ok(ProfileCount { total, with_cfg })
+}
+
+/// Detects the grid of every Steam profile and flags the ones that differ
+/// from the grid most profiles have. Read-only.
+#[tauri::command]
+async fn compare_profiles(app: tauri::AppHandle) -> Result<Vec<ProfileGrid>, AppError> {
+    let cfg_dirs: Vec<(String, PathBuf)> = list_profile_cfg_dirs()
+        .map_err(AppError::IoError)?
+        .into_iter()
+        .filter(|(user_id, _)| user_id != "0")
+        .collect();
+    let grid_hashes = download_grid_hashes(app.clone()).await?;
+
+    let mut profiles = Vec::new();
+    for (user_id, cfg_dir) in cfg_dirs {
+        let grid = identify_grid_with_hashes(&cfg_dir, &grid_hashes).map_err(AppError::IoError)?;
+        profiles.push(ProfileGrid {
+            user_id,
+            config_path: cfg_dir.to_string_lossy().to_string(),
+            grid,
+            mismatch: false,
+        });
+    }
+
+    let mut counts: HashMap<Option<String>, usize> = HashMap::new();
+    for profile in &profiles {
+        let hash = profile.grid.as_ref().map(|grid| grid.hash.clone());
+        *counts.entry(hash).or_default() += 1;
+    }
+    let majority = counts
+        .into_iter()
+        .max_by_key(|(_, count)| *count)
+        .map(|(hash, _)| hash);
+    if let Some(majority) = majority {
+        for profile in &mut profiles {
+            profile.mismatch = profile.grid.as_ref().map(|grid| &grid.hash) != majority.as_ref();
+        }
+    }
+    Ok(profiles)
+}
+
+/// Times each detection step without modifying anything, to diagnose slow
+/// detection on machines with many Steam profiles. The hash list is fetched
+/// before timing starts, so the detection time is the local part only.
+#[tauri::command]
+async fn benchmark_detection(app: tauri::AppHandle) -> Result<DetectionBenchmark, AppError> {
+    let grid_hashes = download_grid_hashes(app.clone())
+        .await
+        .unwrap_or_else(|_| GridHashes {
+            hashes: HashMap::new(),
+        });
+
+    let started = Instant::now();
+    let config_path = find_dota_config_path()?;
+    let find_elapsed = started.elapsed();
+
+    let detect_started = Instant::now();
+    if let Some(path) = &config_path {
+        detect_current_grid(
+            app.clone(),
+            Some(path.to_string_lossy().to_string()),
+            Some(grid_hashes),
+        )
+        .await?;
+    }
+    let detect_elapsed = detect_started.elapsed();
+    let total_elapsed = started.elapsed();
+
+    // The same Steam roots (library folders and extra roots included) that
+    // find_dota_config_path and list_dota_cfg_dirs walk
+    let profiles_scanned = steam_roots()
+        .map_err(AppError::IoError)?
+        .iter()
+        .map(|steam_path| count_userdata_profiles(steam_path))
+        .sum();
+
+    Ok(DetectionBenchmark {
+        find_config_path_ms: find_elapsed.as_secs_f64() * 1000.0,
+        detect_current_grid_ms: detect_elapsed.as_secs_f64() * 1000.0,
+        total_ms: total_elapsed.as_secs_f64() * 1000.0,
+        profiles_scanned,
+        config_path: config_path.map(|p| p.to_string_lossy().to_string()),
+    })
+}
+
+/// Renders a grid as plain text (config and category headers followed by hero
+/// names) for pasting into chat. Heroes without metadata are shown by id.
+#[tauri::command]
+async fn describe_grid(app: tauri::AppHandle, source: String) -> Result<String, AppError> {
+    let grid = parse_grid_config(&load_grid_source(&app, &source).await?)?;
+    let heroes = load_hero_names(&app).await;
+
+    let mut description = String::new();
+    for config in &grid.configs {
+        description.push_str(&format!("=== {} ===\n", config.config_name));
+        for category in &config.categories {
+            let names: Vec<String> = category
+                .hero_ids
+                .iter()
+                .map(|id| hero_display_name(&heroes, *id))
+                .collect();
+            description.push_str(&format!(
+                "[{}] ({})\n{}\n",
+                category.category_name,
+                names.len(),
+                names.join(", ")
+            ));
+        }
+        description.push('\n');
+    }
+    Ok(description.trim_end().to_string())
+}
+
+/// Finds hero ids in a grid (URL or local path) that are not in the current
+/// roster, e.g. after Valve removes a hero. If the grid is a published one
+/// and a newer grid of its type exists, that grid is suggested as the fix.
+#[tauri::command]
+async fn find_stale_hero_references(
+    app: tauri::AppHandle,
+    source: String,
+) -> Result<StaleHeroReport, AppError> {
+    let content = load_grid_source(&app, &source).await?;
+    let grid = parse_grid_config(&content)?;
+    let heroes = load_hero_names(&app).await;
+    if heroes.is_empty() {
+        return Err(AppError::NetworkError(
+            "Hero metadata is unavailable, cannot check for stale heroes".to_string(),
+        ));
+    }
+
+    let mut stale: BTreeMap<u32, Vec<String>> = BTreeMap::new();
+    for config in &grid.configs {
+        for category in &config.categories {
+            for hero_id in &category.hero_ids {
+                if !heroes.contains_key(hero_id) {
+                    let place = format!("{} / {}", config.config_name, category.category_name);
+                    let places = stale.entry(*hero_id).or_default();
+                    if !places.contains(&place) {
+                        places.push(place);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut suggested_update = None;
+    if !stale.is_empty() {
+        let hash = format!("{:x}", md5::compute(&content));
+        let known = match download_grid_hashes(app).await {
+            Ok(grid_hashes) => find_grid_by_hash(&hash, &grid_hashes),
+            Err(e) => {
+                warn!("Could not identify grid for an update suggestion: {}", e);
+                None
+            }
+        };
+        if let Some(known) = known {
+            match fetch_remote_grids(&app).await {
+                Ok(grids) => {
+                    suggested_update = newest_grid_of_type(&grids, &known.grid_type)
+                        .filter(|newest| newest.parsed_date > known.parsed_date)
+                        .cloned();
+                }
+                Err(e) => warn!("Could not look up the newest grid: {}", e),
+            }
+        }
+    }
+
+    Ok(StaleHeroReport {
+        stale: stale
+            .into_iter()
+            .map(|(hero_id, categories)| StaleHeroReference {
+                hero_id,
+                categories,
+            })
+            .collect(),
+        roster_size: heroes.len(),
+        suggested_update,
+    })
+}
+
+/// Compares the cached grid list with a fresh fetch and returns the grids
+/// added and removed since. The fetch goes through load_remote_grid_list, so
+/// the cache (with its ETag) and the tray are updated as usual.
+#[tauri::command]
+async fn diff_remote_grid_lists(app: tauri::AppHandle) -> Result<GridListDiff, AppError> {
+    let cached = read_grid_list_cache(&app)
+        .map(|cache| cache.grids)
+        .unwrap_or_default();
+    let list = load_remote_grid_list(&app, true).await?;
+    if list.stale {
+        // The fetch failed and the cache came back, which would diff as empty
+        return Err(AppError::NetworkError(
+            "Failed to fetch the grid list".to_string(),
+        ));
+    }
+    let fresh = list.grids;
+
+    let cached_names: HashSet<&str> = cached.iter().map(|grid| grid.name.as_str()).collect();
+    let fresh_names: HashSet<&str> = fresh.iter().map(|grid| grid.name.as_str()).collect();
+
+    let added = fresh
+        .iter()
+        .filter(|grid| !cached_names.contains(grid.name.as_str()))
+        .cloned()
+        .collect();
+    let removed = cached
+        .iter()
+        .filter(|grid| !fresh_names.contains(grid.name.as_str()))
+        .cloned()
+        .collect();
+
+    Ok(GridListDiff { added, removed })
+}
+
+/// Scores the newest grid of each type by how much of the current hero roster
+/// it places, so users can avoid grids that are missing new heroes.
+#[tauri::command]
+async fn suggest_best_grid(app: tauri::AppHandle) -> Result<GridSuggestion, AppError> {
+    let roster: HashSet<u32> = load_hero_names(&app).await.into_keys().collect();
+    if roster.is_empty() {
+        return Err(AppError::NetworkError(
+            "Hero metadata is unavailable, cannot score grids".to_string(),
+        ));
+    }
+
+    let grids = fetch_remote_grids(&app).await?;
+    let mut ranking = Vec::new();
+    for grid_type in ["d2pt", "high_winrate", "most_played"] {
+        let Some(grid) = newest_grid_of_type(&grids, grid_type) else {
+            continue;
+        };
+        let content = match fetch_grid_bytes(&app, &grid.download_url).await {
+            Ok(content) => content,
+            Err(e) => {
+                warn!("Skipping {} while scoring: {}", grid.name, e);
+                continue;
+            }
+        };
+        let hero_ids = match parse_grid_config(&content) {
+            Ok(parsed) => grid_hero_ids(&parsed),
+            Err(e) => {
+                warn!("Skipping {} while scoring: {}", grid.name, e);
+                continue;
+            }
+        };
+
+        let mut missing_hero_ids: Vec<u32> = roster.difference(&hero_ids).copied().collect();
+        missing_hero_ids.sort_unstable();
+        let covered = roster.len() - missing_hero_ids.len();
+        ranking.push(GridCoverage {
+            grid: grid.clone(),
+            covered,
+            roster_size: roster.len(),
+            score: covered as f64 / roster.len() as f64,
+            missing_hero_ids,
+        });
+    }
+
+    ranking.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| b.grid.parsed_date.cmp(&a.grid.parsed_date))
+    });
+    Ok(GridSuggestion {
+        best: ranking.first().cloned(),
+        ranking,
+    })
+}
+
+#[tauri::command]
+async fn list_grids_for_patch(app: tauri::AppHandle, patch: String) -> Result<Vec<Grid>, AppError> {
+    let wanted = normalize_patch(&patch);
+    let mut grids: Vec<Grid> = fetch_remote_grids(&app)
+        .await?
+        .into_iter()
+        .filter(|grid| {
+            grid.patch
+                .as_deref()
+                .is_some_and(|p| normalize_patch(p) == wanted)
+        })
+        .collect();
+    grids.sort_by(|a, b| b.parsed_date.cmp(&a.parsed_date));
+    Ok(grids)
+}
+
+/// Returns a grid (URL or local path) as canonically sorted, pretty-printed
+/// JSON for stable diffs. Only ordering changes; positions and sizes are
+/// kept, so the result isn't meant to be activated.
+#[tauri::command]
+async fn normalize_grid(app: tauri::AppHandle, source: String) -> Result<String, AppError> {
+    let grid = parse_grid_config(&load_grid_source(&app, &source).await?)?;
+    serde_json::to_string_pretty(&normalize_grid_config(grid))
+        .map_err(|e| AppError::Other(e.to_string()))
+}
+
+/// Grids published between `start_date` and `end_date` (YYYY-MM-DD, both
+/// inclusive), optionally of one type, oldest first.
+#[tauri::command]
+async fn list_grids_in_range(
+    app: tauri::AppHandle,
+    start_date: String,
+    end_date: String,
+    grid_type: Option<String>,
+) -> Result<Vec<Grid>, AppError> {
+    let parse = |date: &str| {
+        chrono::NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+            .map_err(|_| format!("Invalid date \"{}\" (expected YYYY-MM-DD)", date))
+    };
+    let start = parse(&start_date)?;
+    let end = parse(&end_date)?;
+    if start > end {
+        return Err(AppError::Other(format!(
+            "{} is after {}",
+            start_date, end_date
+        )));
+    }
+
+    let mut grids: Vec<Grid> = fetch_remote_grids(&app)
+        .await?
+        .into_iter()
+        .filter(|grid| {
+            grid_type
+                .as_deref()
+                .is_none_or(|wanted| classify_grid_type(&grid.name) == wanted)
+        })
+        .filter(|grid| {
+            grid.parsed_date
+                .is_some_and(|date| start <= date && date <= end)
+        })
+        .collect();
+    grids.sort_by_key(|grid| grid.parsed_date);
+    Ok(grids)
+}
+
+/// Downloads a grid and writes it to an arbitrary file (or into a directory as
+/// hero_grid_config.json) without touching Dota's detected config.
+#[tauri::command]
+async fn apply_grid_to_path(
+    app: tauri::AppHandle,
+    download_url: String,
+    dest_path: String,
+) -> Result<u64, AppError> {
+    check_download_source(&app, &download_url)?;
+    let content = fetch_grid_bytes(&app, &download_url).await?;
+    parse_grid_config(&content)?;
+
+    let mut dest = PathBuf::from(dest_path);
+    if dest.is_dir() {
+        dest = dest.join(GRID_FILE_NAME);
+    }
+    if let Some(parent) = dest.parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent)
+                .map_err(|e| AppError::IoError(format!("Failed to create {:?}: {}", parent, e)))?;
+        }
+    }
+
+    write_file_atomic(&dest, &content).map_err(AppError::IoError)?;
+    let name = download_url.rsplit('/').next().unwrap_or(&download_url);
+    let hash = format!("{:x}", md5::compute(&content));
+    emit_grid_applied(&app, &dest, name, &download_url, &hash, false);
+    Ok(content.len() as u64)
+}
+
+/// Whether the machine runs on "ac" power, "battery" or "unknown".
+#[cfg(target_os = "linux")]
+fn power_state() -> &'static str {
+    let Ok(entries) = fs::read_dir("/sys/class/power_supply") else {
+        return "unknown";
+    };
+    let mut has_battery = false;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let kind = fs::read_to_string(path.join("type")).unwrap_or_default();
+        match kind.trim() {
+            "Mains" | "USB" => {
+                let online = fs::read_to_string(path.join("online")).unwrap_or_default();
+                if online.trim() == "1" {
+                    return "ac";
+                }
+            }
+            "Battery" => has_battery = true,
+            _ => {}
+        }
+    }
+    if has_battery {
+        "battery"
+    } else {
+        "unknown"
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn power_state() -> &'static str {
+    let output = std::process::Command::new("pmset")
+        .args(["-g", "batt"])
+        .output();
+    match output {
+        Ok(output) => {
+            let text = String::from_utf8_lossy(&output.stdout);
+            if text.contains("'AC Power'") {
+                "ac"
+            } else if text.contains("'Battery Power'") {
+                "battery"
+            } else {
+                "unknown"
+            }
+        }
+        Err(_) => "unknown",
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn power_state() -> &'static str {
+    use winapi::um::winbase::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let mut status: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+    if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+        return "unknown";
+    }
+    match status.ACLineStatus {
+        0 => "battery",
+        1 => "ac",
+        _ => "unknown",
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn power_state() -> &'static str {
+    "unknown"
+}
+
+/// Whether Dota 2 is running, so the UI can warn before changing the grid.
+#[tauri::command]
+fn is_dota_running() -> Result<bool, AppError> {
+    dota_running().map_err(AppError::IoError)
+}
+
+/// Whether a Dota 2 process is running, found by scanning /proc for "dota2".
+#[cfg(target_os = "linux")]
+fn dota_running() -> Result<bool, String> {
+    let entries = fs::read_dir("/proc").map_err(|e| format!("Failed to list processes: {}", e))?;
+    Ok(entries.flatten().any(|entry| {
+        fs::read_to_string(entry.path().join("comm")).is_ok_and(|comm| comm.trim() == "dota2")
+    }))
+}
+
+#[cfg(target_os = "macos")]
+fn dota_running() -> Result<bool, String> {
+    let status = std::process::Command::new("pgrep")
+        .args(["-x", "dota2"])
+        .output()
+        .map_err(|e| format!("Failed to list processes: {}", e))?
+        .status;
+    // pgrep exits with 1 when nothing matched and 2+ on errors
+    match status.code() {
+        Some(0) => Ok(true),
+        Some(1) => Ok(false),
+        _ => Err(format!("pgrep failed: {}", status)),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn dota_running() -> Result<bool, String> {
+    use winapi::um::handleapi::{CloseHandle, INVALID_HANDLE_VALUE};
+    use winapi::um::tlhelp32::{
+        CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W,
+        TH32CS_SNAPPROCESS,
+    };
+
+    let snapshot = unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) };
+    if snapshot == INVALID_HANDLE_VALUE {
+        return Err("Failed to list processes".to_string());
+    }
+    let mut entry: PROCESSENTRY32W = unsafe { std::mem::zeroed() };
+    entry.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as u32;
+    let mut found = false;
+    let mut has_entry = unsafe { Process32FirstW(snapshot, &mut entry) } != 0;
+    while has_entry {
+        let len = entry
+            .szExeFile
+            .iter()
+            .position(|&c| c == 0)
+            .unwrap_or(entry.szExeFile.len());
+        if String::from_utf16_lossy(&entry.szExeFile[..len]).eq_ignore_ascii_case("dota2.exe") {
+            found = true;
+            break;
+        }
+        has_entry = unsafe { Process32NextW(snapshot, &mut entry) } != 0;
+    }
+    unsafe { CloseHandle(snapshot) };
+    Ok(found)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn dota_running() -> Result<bool, String> {
+    Ok(false)
+}
+
+/// Why an update check should be skipped on battery under the configured
+/// policy, or None to run it. Unknown power state always runs the check.
+fn battery_skip_reason(app: &tauri::AppHandle) -> Option<String> {
+    if power_state() != "battery" {
+        return None;
+    }
+    match get_battery_check_policy(app.clone()).as_str() {
+        "skip" => Some("Skipped while on battery".to_string()),
+        "reduced" => {
+            // Skipped ticks are recorded too, so pace by the last real check
+            let last_checked = read_setting::<AutoUpdateStatus>(app, "auto_update_status")
+                .and_then(|status| status.last_checked.or(Some(status.last_run)))
+                .unwrap_or(0);
+            (unix_now().saturating_sub(last_checked) < BATTERY_CHECK_INTERVAL_SECS).then(|| {
+                format!(
+                    "Skipped while on battery; checks run at most every {} hours",
+                    BATTERY_CHECK_INTERVAL_SECS / 3600
+                )
+            })
+        }
+        _ => None,
+    }
+}
+
+#[tauri::command]
+fn get_battery_check_policy(app: tauri::AppHandle) -> String {
+    read_setting(&app, "battery_check_policy").unwrap_or_else(|| "reduced".to_string())
+}
+
+/// How update checks behave on battery: "normal" always checks, "reduced"
+/// checks at most every BATTERY_CHECK_INTERVAL_SECS and "skip" never checks.
+#[tauri::command]
+fn set_battery_check_policy(app: tauri::AppHandle, policy: String) -> Result<(), AppError> {
+    if !["normal", "reduced", "skip"].contains(&policy.as_str()) {
+        return Err(AppError::Other(format!(
+            "Unknown battery policy \"{}\" (expected normal, reduced or skip)",
+            policy
+        )));
+    }
+    info!("Setting battery_check_policy to: {}", policy);
+    write_setting(&app, "battery_check_policy", policy).map_err(AppError::IoError)
+}
+
+/// Compares the installed grid against the newest published grid of the same
+/// type. Every run is recorded so get_auto_update_status can report it. On
+/// battery the check may be skipped, see set_battery_check_policy.
+#[tauri::command]
+async fn check_for_updates(
+    app: tauri::AppHandle,
+    dota_config_path: Option<String>,
+) -> Result<UpdateCheckResult, AppError> {
+    if let Some(reason) = battery_skip_reason(&app) {
+        info!("Update check: {}", reason);
+        let skipped = UpdateCheckResult {
+            current: None,
+            latest: None,
+            update_available: false,
+            clock_skew_warning: None,
+            skipped_reason: Some(reason),
+        };
+        record_auto_update_run(&app, "skipped", Some(&skipped), None);
+        return Ok(skipped);
+    }
+    let result = match resolve_config_dir(&app, dota_config_path) {
+        Ok(config_dir) => run_update_check(&app, &config_dir).await,
+        Err(e) => Err(AppError::ConfigNotFound(e)),
+    };
+    match &result {
+        Ok(check) => record_auto_update_run(&app, "checked", Some(check), None),
+        Err(e) => record_auto_update_run(&app, "failed", None, Some(e.to_string())),
+    }
+    result
+}
+
+/// Runs one scheduled update check. When a newer grid is found, emits
+/// "grid-update-available" and shows a notification, but only once per
+/// grid so the same release isn't announced on every run.
+async fn run_scheduled_update_check(app: &tauri::AppHandle) {
+    let check = match check_for_updates(app.clone(), None).await {
+        Ok(check) => check,
+        Err(e) => {
+            error!("Scheduled update check failed: {}", e);
+            return;
+        }
+    };
+    if let Some(reason) = &check.skipped_reason {
+        info!("Scheduled update check skipped: {}", reason);
+        return;
+    }
+    if get_auto_apply_grid_type(app.clone()).is_some() {
+        match run_auto_apply(app).await {
+            Ok(Some(grid)) => {
+                info!("Auto-applied {}", grid.name);
+                let applied = UpdateCheckResult {
+                    latest: Some(grid.clone()),
+                    ..check.clone()
+                };
+                record_auto_update_run(app, "updated", Some(&applied), None);
+                if let Err(e) = app
+                    .notification()
+                    .builder()
+                    .title("Grid updated")
+                    .body(format!("{} was installed automatically", grid.name))
+                    .show()
+                {
+                    warn!("Failed to show notification: {}", e);
+                }
+                let _ = write_setting(app, "last_notified_grid", &grid.name);
+                return;
+            }
+            Ok(None) => {}
+            Err(e) => info!("Auto-apply skipped: {}", e),
+        }
+    }
+    let Some(latest) = check.latest.as_ref().filter(|_| check.update_available) else {
+        return;
+    };
+    let last_notified: Option<String> = read_setting(app, "last_notified_grid");
+    if last_notified.as_deref() == Some(latest.name.as_str()) {
+        return;
+    }
+
+    info!("Newer grid available: {}", latest.name);
+    if let Err(e) = app.emit("grid-update-available", &check) {
+        warn!("Failed to emit grid-update-available: {}", e);
+    }
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title("New grid available")
+        .body(format!("{} has been published", latest.name))
+        .show()
+    {
+        warn!("Failed to show notification: {}", e);
+    }
+    if let Err(e) = write_setting(app, "last_notified_grid", &latest.name) {
+        warn!("Failed to remember notified grid: {}", e);
+    }
+}
+
+/// Installs the newest grid of the auto-apply type if it's newer than the
+/// installed one. Returns the installed grid, None when already up to date,
+/// or an error explaining why it was skipped: Dota running, or a grid that
+/// has no published hash and so can't be verified.
+async fn run_auto_apply(app: &tauri::AppHandle) -> Result<Option<Grid>, String> {
+    let grid_type = get_auto_apply_grid_type(app.clone()).ok_or("Auto-apply is off")?;
+    let config_dir = resolve_config_dir(app, None)?;
+    let current = identify_installed_grid(app, &config_dir).await?;
+    let grids = fetch_remote_grids(app).await?;
+    let latest = newest_grid_of_type(&grids, &grid_type)
+        .cloned()
+        .ok_or_else(|| format!("No published {} grid found", grid_type))?;
+    let up_to_date = current.as_ref().is_some_and(|current| {
+        current.is_known
+            && current.grid_type == grid_type
+            && current.parsed_date >= latest.parsed_date
+    });
+    if up_to_date {
+        return Ok(None);
+    }
+
+    let grid_hashes = download_grid_hashes(app.clone()).await?;
+    if !grid_hashes.hashes.contains_key(&latest.name) {
+        return Err(format!("{} has no published hash to verify", latest.name));
+    }
+    // activate_grid rejects a download that doesn't match the hash
+    let activation = activate_grid_with(
+        app.clone(),
+        latest.name.clone(),
+        latest.download_url.clone(),
+        None,
+        ActivationOptions::default(),
+    )
+    .await?;
+    if !activation.verified {
+        warn!("{} was installed without verification", latest.name);
+    }
+    Ok(Some(latest))
+}
+
+/// Starts the update check loop, replacing any running one.
+fn spawn_update_checker(app: tauri::AppHandle, interval_hours: u64) {
+    let generation = app
+        .state::<UpdateScheduler>()
+        .generation
+        .fetch_add(1, AtomicOrdering::SeqCst)
+        + 1;
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let current = app
+                .state::<UpdateScheduler>()
+                .generation
+                .load(AtomicOrdering::SeqCst);
+            if current != generation {
+                break;
+            }
+            run_scheduled_update_check(&app).await;
+            tokio::time::sleep(Duration::from_secs(interval_hours * 3600)).await;
+        }
+    });
+}
+
+/// The grid type kept up to date automatically, or None when auto-apply is
+/// off.
+#[tauri::command]
+fn get_auto_apply_grid_type(app: tauri::AppHandle) -> Option<String> {
+    read_setting(&app, "auto_apply_grid_type")
+}
+
+/// Turns on automatic installs of new grids of `grid_type` ("d2pt",
+/// "high_winrate" or "most_played") from the background update check, or
+/// turns them off with `None`.
+#[tauri::command]
+fn set_auto_apply_grid_type(
+    app: tauri::AppHandle,
+    grid_type: Option<String>,
+) -> Result<(), AppError> {
+    if let Some(grid_type) = &grid_type {
+        if !["d2pt", "high_winrate", "most_played"].contains(&grid_type.as_str()) {
+            return Err(AppError::Other(format!(
+                "Unknown grid type \"{}\"",
+                grid_type
+            )));
+        }
+    }
+    info!("Setting auto_apply_grid_type to: {:?}", grid_type);
+    write_setting(&app, "auto_apply_grid_type", grid_type).map_err(AppError::IoError)
+}
+
+#[tauri::command]
+fn get_update_check_interval_hours(app: tauri::AppHandle) -> u64 {
+    read_setting(&app, "update_check_interval_hours").unwrap_or(DEFAULT_UPDATE_CHECK_INTERVAL_HOURS)
+}
+
+/// Sets how often the app checks for newer grids in the background; 0
+/// turns background checks off. Takes effect immediately.
+#[tauri::command]
+fn set_update_check_interval_hours(
+    app: tauri::AppHandle,
+    interval_hours: u64,
+) -> Result<(), AppError> {
+    info!("Setting update_check_interval_hours to: {}", interval_hours);
+    write_setting(&app, "update_check_interval_hours", interval_hours)
+        .map_err(AppError::IoError)?;
+    if interval_hours > 0 {
+        spawn_update_checker(app, interval_hours);
+    } else {
+        app.state::<UpdateScheduler>()
+            .generation
+            .fetch_add(1, AtomicOrdering::SeqCst);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn get_grid_hotkey(app: tauri::AppHandle) -> Option<String> {
+    read_setting(&app, "grid_hotkey")
+}
+
+/// Sets the global shortcut that activates the latest grid of the remembered
+/// type (e.g. "CmdOrCtrl+Shift+G"), or clears it with `None`. A combination
+/// another app already holds is rejected and the previous hotkey is kept.
+#[tauri::command]
+fn set_grid_hotkey(app: tauri::AppHandle, hotkey: Option<String>) -> Result<(), AppError> {
+    info!("Setting grid_hotkey to: {:?}", hotkey);
+    let shortcut = hotkey
+        .as_deref()
+        .map(|hotkey| {
+            hotkey
+                .parse::<Shortcut>()
+                .map_err(|e| format!("Invalid hotkey {}: {}", hotkey, e))
+        })
+        .transpose()?;
+
+    let previous = get_grid_hotkey(app.clone()).and_then(|hotkey| hotkey.parse::<Shortcut>().ok());
+    let shortcuts = app.global_shortcut();
+    if let Some(previous) = previous {
+        if let Err(e) = shortcuts.unregister(previous) {
+            warn!("Failed to unregister previous hotkey: {}", e);
+        }
+    }
+    if let Some(shortcut) = shortcut {
+        if let Err(e) = shortcuts.register(shortcut) {
+            if let Some(previous) = previous {
+                let _ = shortcuts.register(previous);
+            }
+            return Err(AppError::Other(format!(
+                "Couldn't register {}, it may already be used by another application: {}",
+                hotkey.unwrap_or_default(),
+                e
+            )));
+        }
+    }
+    write_setting(&app, "grid_hotkey", hotkey).map_err(AppError::IoError)
+}
+
+/// Registers the saved grid hotkey at startup.
+fn register_grid_hotkey(app: &tauri::AppHandle) {
+    let Some(hotkey) = get_grid_hotkey(app.clone()) else {
+        return;
+    };
+    let result = hotkey
+        .parse::<Shortcut>()
+        .map_err(|e| e.to_string())
+        .and_then(|shortcut| {
+            app.global_shortcut()
+                .register(shortcut)
+                .map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        warn!("Failed to register grid hotkey {}: {}", hotkey, e);
+    }
+}
+
+/// Activates the newest grid of the remembered type through activate_grid,
+/// so the usual backup and Dota-running checks apply, and notifies the
+/// result.
+fn handle_grid_hotkey(app: &tauri::AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = async {
+            let grid_type = get_remembered_grid_type(app.clone());
+            let grids = load_remote_grid_list(&app, false).await?.grids;
+            let latest = newest_grid_of_type(&grids, &grid_type)
+                .cloned()
+                .ok_or_else(|| format!("No published {} grid found", grid_type))?;
+            activate_grid_with(
+                app.clone(),
+                latest.name.clone(),
+                latest.download_url.clone(),
+                None,
+                ActivationOptions::default(),
+            )
+            .await?;
+            Ok::<_, String>(latest)
+        }
+        .await;
+        let (title, body) = match result {
+            Ok(grid) => ("Grid activated", grid.name),
+            Err(e) => ("Failed to activate grid", e),
+        };
+        info!("{} from hotkey: {}", title, body);
+        if let Err(e) = app.notification().builder().title(title).body(body).show() {
+            warn!("Failed to show notification: {}", e);
+        }
+    });
+}
+
+/// Days between the installed grid's publish date and today (UTC), or `None`
+/// when the grid isn't a known published one.
+#[tauri::command]
+async fn current_grid_age_days(
+    app: tauri::AppHandle,
+    dota_config_path: Option<String>,
+) -> Result<Option<i64>, AppError> {
+    let config_dir =
+        resolve_config_dir(&app, dota_config_path).map_err(AppError::ConfigNotFound)?;
+    let detected = match identify_installed_grid(&app, &config_dir).await? {
+        Some(grid) => grid,
+        None => return Ok(None),
+    };
+    let Some(published) = detected.parsed_date else {
+        return Ok(None);
+    };
+    let today = chrono::Utc::now().date_naive();
+    Ok(Some((today - published).num_days()))
+}
+
+/// Installs the newest grid of the remembered type only if the installed grid
+/// is the one the app last wrote (or there is none). Grids the user changed
+/// or made by hand are left alone.
+#[tauri::command]
+async fn activate_if_unmodified(
+    app: tauri::AppHandle,
+    dota_config_path: Option<String>,
+) -> Result<ConservativeActivation, AppError> {
+    let config_dir =
+        resolve_config_dir(&app, dota_config_path).map_err(AppError::ConfigNotFound)?;
+    let grid_file_path = config_dir.join(GRID_FILE_NAME);
+    let refuse = |reason: &str, grid: Option<Grid>| ConservativeActivation {
+        applied: false,
+        reason: reason.to_string(),
+        grid,
+    };
+
+    let current_hash = if grid_file_path.exists() {
+        Some(hash_file(&grid_file_path).map_err(AppError::IoError)?)
+    } else {
+        None
+    };
+    if let Some(hash) = &current_hash {
+        let last_activated = last_activated_hash(&app, &config_dir);
+        if last_activated.as_ref() != Some(hash) {
+            return Ok(refuse(
+                "The installed grid was not installed by this app or has been modified since, so it was left untouched.",
+                None,
+            ));
+        }
+    }
+
+    let grid_type = get_remembered_grid_type(app.clone());
+    let grids = fetch_remote_grids(&app).await?;
+    let latest = newest_grid_of_type(&grids, &grid_type)
+        .cloned()
+        .ok_or_else(|| AppError::Other(format!("No published {} grid found", grid_type)))?;
+    let latest_hash = download_grid_hashes(app.clone())
+        .await?
+        .hashes
+        .get(&latest.name)
+        .cloned();
+    if current_hash.is_some() && current_hash == latest_hash {
+        return Ok(refuse(
+            "The latest grid is already installed.",
+            Some(latest),
+        ));
+    }
+
+    install_grid_with_retry(&app, &config_dir, &latest.download_url).await?;
+    Ok(ConservativeActivation {
+        applied: true,
+        reason: format!("Installed {}", latest.name),
+        grid: Some(latest),
+    })
+}
+
+#[tauri::command]
+async fn check_clock_skew(app: tauri::AppHandle) -> Result<ClockSkew, AppError> {
+    measure_clock_skew(&app).await
+}
+
+#[tauri::command]
+fn get_auto_update_status(app: tauri::AppHandle) -> Option<AutoUpdateStatus> {
+    read_setting::<AutoUpdateStatus>(&app, "auto_update_status").map(|status| AutoUpdateStatus {
+        power_state: Some(power_state().to_string()),
+        ..status
+    })
+}
+
+/// Tells whether the installed config is still Dota's untouched default, so
+/// the UI can suggest installing a real grid.
+#[tauri::command]
+fn is_default_grid(
+    app: tauri::AppHandle,
+    dota_config_path: Option<String>,
+) -> Result<DefaultGridStatus, AppError> {
+    let config_dir =
+        resolve_config_dir(&app, dota_config_path).map_err(AppError::ConfigNotFound)?;
+    let grid_file_path = config_dir.join(GRID_FILE_NAME);
+    let status = |is_default: bool, state: &str| DefaultGridStatus {
+        is_default,
+        state: state.to_string(),
+    };
+
+    if !grid_file_path.exists() {
+        return Ok(status(true, "missing"));
+    }
+    let content = fs::read(&grid_file_path)
+        .map_err(|e| AppError::IoError(format!("Failed to read grid file: {}", e)))?;
+    if content.iter().all(|b| b.is_ascii_whitespace()) {
+        return Ok(status(true, "empty"));
+    }
+
+    let value: serde_json::Value =
+        serde_json::from_slice(&content).map_err(|e| format!("Invalid grid file: {}", e))?;
+    let fingerprint: serde_json::Value =
+        serde_json::from_str(DEFAULT_GRID_FINGERPRINT).map_err(|e| e.to_string())?;
+    // Compared structurally, so formatting differences don't matter. A grid
+    // without a single placed hero is treated as default too.
+    let has_heroes = serde_json::from_value::<HeroGridConfig>(value.clone())
+        .map(|grid| !grid_hero_ids(&grid).is_empty())
+        .unwrap_or(true);
+    if value == fingerprint || !has_heroes {
+        Ok(status(true, "default"))
+    } else {
+        Ok(status(false, "custom"))
+    }
+}
+
+fn read_installed_grid(config_dir: &Path) -> Result<HeroGridConfig, AppError> {
+    let content = fs::read(config_dir.join(GRID_FILE_NAME))
+        .map_err(|e| AppError::IoError(format!("Failed to read grid file: {}", e)))?;
+    Ok(parse_grid_config(&content)?)
+}
+
+fn colliding_config_names(grid: &HeroGridConfig) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut collisions = Vec::new();
+    for page in &grid.configs {
+        if !seen.insert(page.config_name.as_str()) && !collisions.contains(&page.config_name) {
+            collisions.push(page.config_name.clone());
+        }
+    }
+    collisions
+}
+
+/// Lists config names used by more than one page of the installed grid,
+/// usually left behind by a sloppy merge.
+#[tauri::command]
+fn detect_name_collisions(
+    app: tauri::AppHandle,
+    dota_config_path: Option<String>,
+) -> Result<Vec<String>, AppError> {
+    let config_dir =
+        resolve_config_dir(&app, dota_config_path).map_err(AppError::ConfigNotFound)?;
+    Ok(colliding_config_names(&read_installed_grid(&config_dir)?))
+}
+
+/// Renames every repeated config name after its first use to "<name> (2)",
+/// "<name> (3)"..., skipping names already taken. The grid is backed up
+/// first. Returns the renames; nothing is written when there are none.
+#[tauri::command]
+fn fix_name_collisions(
+    app: tauri::AppHandle,
+    dota_config_path: Option<String>,
+) -> Result<Vec<RenamedConfig>, AppError> {
+    let config_dir =
+        resolve_config_dir(&app, dota_config_path).map_err(AppError::ConfigNotFound)?;
+    let mut grid = read_installed_grid(&config_dir)?;
+    if colliding_config_names(&grid).is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut taken: HashSet<String> = grid
+        .configs
+        .iter()
+        .map(|page| page.config_name.clone())
+        .collect();
+    let mut seen = HashSet::new();
+    let mut renamed = Vec::new();
+    for page in &mut grid.configs {
+        if seen.insert(page.config_name.clone()) {
+            continue;
+        }
+        let new_name = (2..)
+            .map(|n| format!("{} ({})", page.config_name, n))
+            .find(|name| !taken.contains(name))
+            .unwrap_or_default();
+        taken.insert(new_name.clone());
+        seen.insert(new_name.clone());
+        renamed.push(RenamedConfig {
+            from: std::mem::replace(&mut page.config_name, new_name.clone()),
+            to: new_name,
+        });
+    }
+
+    let backup_made = backup_grid_file(&config_dir)
+        .map_err(AppError::IoError)?
+        .is_some();
+    prune_backups(&app, &config_dir);
+    let content = serde_json::to_vec_pretty(&grid).map_err(|e| e.to_string())?;
+    let grid_file_path = config_dir.join(GRID_FILE_NAME);
+    write_file_atomic(&grid_file_path, &content).map_err(AppError::IoError)?;
+    emit_grid_applied(
+        &app,
+        &grid_file_path,
+        GRID_FILE_NAME,
+        GRID_FILE_NAME,
+        &format!("{:x}", md5::compute(&content)),
+        backup_made,
+    );
+    Ok(renamed)
+}
+
+/// Lists hero ids in a grid (URL or local path) outside MIN_HERO_ID..=
+/// MAX_HERO_ID, sorted and without duplicates. Works without hero metadata.
+#[tauri::command]
+async fn validate_hero_ids(app: tauri::AppHandle, source: String) -> Result<Vec<u32>, AppError> {
+    let grid = parse_grid_config(&load_grid_source(&app, &source).await?)?;
+    let mut invalid: Vec<u32> = grid_hero_ids(&grid)
+        .into_iter()
+        .filter(|id| !(MIN_HERO_ID..=MAX_HERO_ID).contains(id))
+        .collect();
+    invalid.sort_unstable();
+    invalid.dedup();
+    Ok(invalid)
+}
+
+/// Counts the configs in the installed grid and warns when merges have
+/// brought it close to (or past) what Dota's picker can show.
+#[tauri::command]
+fn check_config_count(
+    app: tauri::AppHandle,
+    dota_config_path: Option<String>,
+) -> Result<ConfigCountStatus, AppError> {
+    let config_dir =
+        resolve_config_dir(&app, dota_config_path).map_err(AppError::ConfigNotFound)?;
+    let count = read_installed_grid(&config_dir)?.configs.len();
+    let near_limit = count + CONFIG_COUNT_WARNING_MARGIN >= MAX_GRID_CONFIGS;
+    let warning = if count > MAX_GRID_CONFIGS {
+        Some(format!(
+            "The grid has {} configs but Dota only shows {}; the rest can't be picked in game",
+            count, MAX_GRID_CONFIGS
+        ))
+    } else if near_limit {
+        Some(format!(
+            "The grid has {} of the {} configs Dota can show",
+            count, MAX_GRID_CONFIGS
+        ))
+    } else {
+        None
+    };
+    Ok(ConfigCountStatus {
+        count,
+        limit: MAX_GRID_CONFIGS,
+        near_limit,
+        warning,
+    })
+}
+
+/// Validates a grid file, parsing it straight from a buffered reader instead
+/// of reading the whole file into memory first. The hash is computed in a
+/// separate streaming pass.
+#[tauri::command]
+fn validate_grid_file(path: String) -> Result<GridValidation, AppError> {
+    let file = fs::File::open(&path)
+        .map_err(|e| AppError::IoError(format!("Failed to read {}: {}", path, e)))?;
+    let mut deserializer = serde_json::Deserializer::from_reader(std::io::BufReader::new(file));
+    let value = serde_json::Value::deserialize(&mut deserializer)
+        .and_then(|value| deserializer.end().map(|_| value))
+        .map_err(|e| format!("Invalid JSON: {}", e))?;
+
+    let mut validation = validate_grid_value(&value);
+    validation.hash = Some(hash_file(Path::new(&path)).map_err(AppError::IoError)?);
+    Ok(validation)
+}
+
+/// Queries GitHub's rate_limit endpoint, which doesn't count against the
+/// limit itself, so the UI can disable refreshing until the reset time.
+#[tauri::command]
+async fn get_rate_limit_status(app: tauri::AppHandle) -> Result<RateLimitStatus, AppError> {
+    let token = github_token(&app);
+    let client = http_client(&app);
+    let request = github_request(
+        &client,
+        "https://api.github.com/rate_limit",
+        token.as_deref(),
+    );
+    let response = send_with_retry(&app, request)
+        .await
+        .map_err(|e| AppError::NetworkError(format!("Failed to fetch GitHub rate limit: {}", e)))?;
+
+    if !response.status().is_success() {
+        return Err(github_status_error(&response, "fetch GitHub rate limit"));
+    }
+
+    let body: GithubRateLimitResponse = response
+        .json()
+        .await
+        .map_err(|e| AppError::NetworkError(e.to_string()))?;
+    let core = body.resources.core;
+    Ok(RateLimitStatus {
+        limit: core.limit,
+        remaining: core.remaining,
+        used: core.used,
+        reset_at: core.reset,
+        seconds_until_reset: core.reset.saturating_sub(unix_now()),
+        authenticated: token.is_some(),
+    })
+}
+
+// Mirrors of the grid types holding only the fields the app understands
+// (those of the newest supported version). detect_unknown_grid_fields
+// deserializes into them and collects whatever serde_ignored reports.
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct KnownHeroGridConfig {
+    configs: Vec<KnownGridConfigPage>,
+    version: Option<IgnoredAny>,
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct KnownGridConfigPage {
+    config_name: IgnoredAny,
+    categories: Vec<KnownGridCategory>,
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct KnownGridCategory {
+    category_name: IgnoredAny,
+    x_position: Option<IgnoredAny>,
+    y_position: Option<IgnoredAny>,
+    width: Option<IgnoredAny>,
+    height: Option<IgnoredAny>,
+    hero_ids: IgnoredAny,
+}
+
+// Formats a serde_ignored path as e.g. "configs[0].categories[1].color"
+fn ignored_field_path(path: &serde_ignored::Path) -> String {
+    match path {
+        serde_ignored::Path::Root => String::new(),
+        serde_ignored::Path::Seq { parent, index } => {
+            format!("{}[{}]", ignored_field_path(parent), index)
+        }
+        serde_ignored::Path::Map { parent, key } => match ignored_field_path(parent) {
+            parent if parent.is_empty() => key.clone(),
+            parent => format!("{}.{}", parent, key),
+        },
+        serde_ignored::Path::Some { parent }
+        | serde_ignored::Path::NewtypeStruct { parent }
+        | serde_ignored::Path::NewtypeVariant { parent } => ignored_field_path(parent),
+    }
+}
+
+/// Lists the JSON paths of fields the app doesn't know about. The typed
+/// parse keeps them in `extra`, but merges that rebuild configs may not carry
+/// them over, so the UI can warn before activating.
+#[tauri::command]
+async fn detect_unknown_grid_fields(
+    app: tauri::AppHandle,
+    source: String,
+) -> Result<Vec<String>, AppError> {
+    let content = load_grid_source(&app, &source).await?;
+    // Run first for its descriptive errors on a broken file
+    parse_grid_config(&content)?;
+
+    let mut unknown = Vec::new();
+    let mut deserializer = serde_json::Deserializer::from_slice(&content);
+    let _: KnownHeroGridConfig = serde_ignored::deserialize(&mut deserializer, |path| {
+        unknown.push(ignored_field_path(&path))
+    })
+    .map_err(|e| format!("Failed to parse grid: {}", e))?;
+    Ok(unknown)
+}
+
+/// Gives the owner write access to `path`. Returns whether anything changed.
+#[cfg(unix)]
+fn make_writable(path: &Path) -> Result<bool, String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut permissions = fs::metadata(path)
+        .map_err(|e| format!("Failed to read permissions of {:?}: {}", path, e))?
+        .permissions();
+    let mode = permissions.mode();
+    if mode & 0o200 != 0 {
+        return Ok(false);
+    }
+    permissions.set_mode(mode | 0o200);
+    fs::set_permissions(path, permissions)
+        .map_err(|e| format!("Failed to make {:?} writable: {}", path, e))?;
+    Ok(true)
+}
+
+/// Clears the read-only attribute of `path`. Returns whether anything changed.
+#[cfg(not(unix))]
+fn make_writable(path: &Path) -> Result<bool, String> {
+    let mut permissions = fs::metadata(path)
+        .map_err(|e| format!("Failed to read permissions of {:?}: {}", path, e))?
+        .permissions();
+    if !permissions.readonly() {
+        return Ok(false);
+    }
+    // Only clears the read-only attribute on Windows
+    #[allow(clippy::permissions_set_readonly_false)]
+    permissions.set_readonly(false);
+    fs::set_permissions(path, permissions)
+        .map_err(|e| format!("Failed to clear read-only on {:?}: {}", path, e))?;
+    Ok(true)
+}
+
+/// Makes the cfg directory and grid file writable again if they were marked
+/// read-only, then checks a grid can actually be written there. Returns a
+/// description of each change; empty when nothing needed fixing.
+#[tauri::command]
+fn repair_config_permissions(
+    app: tauri::AppHandle,
+    dota_config_path: Option<String>,
+) -> Result<Vec<String>, AppError> {
+    let config_dir =
+        resolve_config_dir(&app, dota_config_path).map_err(AppError::ConfigNotFound)?;
+    let grid_file_path = config_dir.join(GRID_FILE_NAME);
+
+    let mut changes = Vec::new();
+    if make_writable(&config_dir).map_err(AppError::PermissionDenied)? {
+        changes.push(format!("Made {:?} writable", config_dir));
+    }
+    if grid_file_path.exists()
+        && make_writable(&grid_file_path).map_err(AppError::PermissionDenied)?
+    {
+        changes.push(format!("Made {:?} writable", grid_file_path));
+    }
+
+    check_config_writable(&config_dir)
+        .map_err(|e| AppError::PermissionDenied(format!("Still not writable: {}", e)))?;
+    Ok(changes)
+}
+
+/// Checks a grid can be written to `config_dir` by creating and removing a
+/// probe file, since permission bits (and on Windows the directory's
+/// read-only attribute, which Explorer sets on many folders) don't reliably
+/// predict whether a write succeeds. An existing grid file must also open
+/// for writing.
+fn check_config_writable(config_dir: &Path) -> Result<(), AppError> {
+    if !config_dir.is_dir() {
+        return Err(AppError::ConfigNotFound(format!(
+            "{:?} does not exist",
+            config_dir
+        )));
+    }
+    let classify = |path: &Path, e: std::io::Error| {
+        let message = format!("Can't write to {:?}: {}", path, e);
+        if e.kind() == std::io::ErrorKind::PermissionDenied {
+            AppError::PermissionDenied(message)
+        } else {
+            AppError::IoError(message)
+        }
+    };
+
+    let probe_path = config_dir.join(format!("{}.permission-check", GRID_FILE_NAME));
+    fs::write(&probe_path, b"").map_err(|e| classify(config_dir, e))?;
+    let _ = fs::remove_file(&probe_path);
+    let grid_file_path = config_dir.join(GRID_FILE_NAME);
+    if grid_file_path.exists() {
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&grid_file_path)
+            .map_err(|e| classify(&grid_file_path, e))?;
+    }
+    Ok(())
+}
+
+/// Whether a grid can be written to the given cfg directory (or the detected
+/// one) right now. See check_config_writable.
+#[tauri::command]
+fn can_write_config(app: tauri::AppHandle, path: Option<String>) -> Result<bool, AppError> {
+    let config_dir = resolve_config_dir(&app, path).map_err(AppError::ConfigNotFound)?;
+    match check_config_writable(&config_dir) {
+        Ok(()) => Ok(true),
+        Err(e) => {
+            warn!("Config is not writable: {}", e);
+            Ok(false)
+        }
+    }
+}
+
+/// Self-test for the write path: writes a known grid to a temp directory with
+/// the same atomic write activation uses, reads it back and compares bytes and
+/// hashes. The real Dota config is never touched.
+#[tauri::command]
+fn verify_write_roundtrip() -> RoundtripResult {
+    let expected = SELF_TEST_GRID.as_bytes();
+    let expected_hash = format!("{:x}", md5::compute(expected));
+    let test_dir =
+        std::env::temp_dir().join(format!("d2pt-grid-updater-selftest-{}", std::process::id()));
+
+    let result = (|| -> Result<String, String> {
+        fs::create_dir_all(&test_dir)
+            .map_err(|e| format!("Failed to create temp directory: {}", e))?;
+        let test_path = test_dir.join(GRID_FILE_NAME);
+        write_file_atomic(&test_path, expected)?;
+        let written =
+            fs::read(&test_path).map_err(|e| format!("Failed to read back test file: {}", e))?;
+        let actual_hash = format!("{:x}", md5::compute(&written));
+        if written != expected {
+            return Err(format!(
+                "Read back {} bytes that differ from the {} bytes written (hash {})",
+                written.len(),
+                expected.len(),
+                actual_hash
+            ));
+        }
+        Ok(actual_hash)
+    })();
+    let _ = fs::remove_dir_all(&test_dir);
+
+    match result {
+        Ok(actual_hash) => RoundtripResult {
+            passed: true,
+            expected_hash,
+            actual_hash: Some(actual_hash),
+            error: None,
+        },
+        Err(e) => RoundtripResult {
+            passed: false,
+            expected_hash,
+            actual_hash: None,
+            error: Some(e),
+        },
+    }
+}
+
+#[tauri::command]
+fn get_debug_mode(app: tauri::AppHandle) -> bool {
+    read_setting(&app, "debug_mode").unwrap_or(false)
+}
+
+#[tauri::command]
+fn set_debug_mode(app: tauri::AppHandle, enabled: bool) -> Result<(), AppError> {
+    info!("Setting debug_mode to: {}", enabled);
+    write_setting(&app, "debug_mode", enabled).map_err(AppError::IoError)
+}
+
+/// Debugging aid: returns the unparsed GitHub contents API response for a
+/// repo path (e.g. "grids"). Only available while debug mode is enabled.
+#[tauri::command]
+async fn fetch_raw_contents(app: tauri::AppHandle, path: String) -> Result<String, AppError> {
+    if !get_debug_mode(app.clone()) {
+        return Err(AppError::Other(
+            "fetch_raw_contents requires debug mode to be enabled".to_string(),
+        ));
+    }
+
+    let url = github_contents_url(&app, path.trim_matches('/'));
+    let token = github_token(&app);
+    let request = github_request(&http_client(&app), &url, token.as_deref());
+    let response = send_with_retry(&app, request)
+        .await
+        .map_err(|e| AppError::NetworkError(format!("Failed to fetch {}: {}", url, e)))?;
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| AppError::NetworkError(e.to_string()))?;
+    if !status.is_success() {
+        return Err(AppError::NetworkError(format!(
+            "GitHub returned {} for {}: {}",
+            status, url, body
+        )));
+    }
+    Ok(body)
+}
+
+fn source_info_cache_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("source_info_cache.json"))
+        .map_err(|e| format!("Could not get app data directory: {}", e))
+}
+
+async fn fetch_readme(app: &tauri::AppHandle) -> Result<Option<String>, String> {
+    let source = get_grid_source(app.clone());
+    let url = format!(
+        "https://api.github.com/repos/{}/readme?ref={}",
+        source.repo, source.branch
+    );
+    let token = github_token(app);
+    let request = github_request(&http_client(app), &url, token.as_deref())
+        .header("Accept", "application/vnd.github.raw");
+    let response = send_with_retry(app, request)
+        .await
+        .map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !status.is_success() {
+        return Err(format!("GitHub returned {} for {}", status, url));
+    }
+    response.text().await.map(Some).map_err(|e| e.to_string())
+}
+
+/// Returns the grid repo's README as markdown, or None if it has none. The
+/// result is cached for SOURCE_INFO_TTL_SECS, and a stale copy is used when
+/// GitHub can't be reached.
+#[tauri::command]
+async fn fetch_source_info(app: tauri::AppHandle) -> Result<Option<String>, AppError> {
+    let path = source_info_cache_path(&app).map_err(AppError::IoError)?;
+    let cached: Option<SourceInfoCache> = fs::read(&path)
+        .ok()
+        .and_then(|content| serde_json::from_slice(&content).ok());
+    if let Some(cache) = &cached {
+        if unix_now().saturating_sub(cache.fetched_at) < SOURCE_INFO_TTL_SECS {
+            return Ok(cache.readme.clone());
+        }
+    }
+
+    let readme = match fetch_readme(&app).await {
+        Ok(readme) => readme,
+        Err(e) => {
+            warn!("Failed to fetch README: {}", e);
+            return cached
+                .map(|cache| cache.readme)
+                .ok_or(AppError::NetworkError(e));
+        }
+    };
+    let cache = SourceInfoCache {
+        fetched_at: unix_now(),
+        readme: readme.clone(),
+    };
+    let result = serde_json::to_vec(&cache)
+        .map_err(|e| e.to_string())
+        .and_then(|content| {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+            }
+            write_file_atomic(&path, &content)
+        });
+    if let Err(e) = result {
+        warn!("Failed to cache README: {}", e);
+    }
+    Ok(readme)
+}
+
+#[tauri::command]
+fn get_rank(app: tauri::AppHandle) -> Option<String> {
+    read_setting(&app, "rank")
+}
+
+#[tauri::command]
+fn set_rank(app: tauri::AppHandle, rank: Option<String>) -> Result<(), AppError> {
+    let rank = rank.map(|r| r.trim().to_lowercase());
+    if let Some(r) = &rank {
+        if !RANK_BRACKETS.contains(&r.as_str()) {
+            return Err(AppError::Other(format!(
+                "Unknown rank \"{}\" (expected one of: {})",
+                r,
+                RANK_BRACKETS.join(", ")
+            )));
+        }
+    }
+    info!("Setting rank to: {:?}", rank);
+    write_setting(&app, "rank", rank).map_err(AppError::IoError)
+}
+
+/// Picks the newest grid for the user's rank bracket, preferring the d2pt
+/// rating type, and falls back to the newest general d2pt grid.
+#[tauri::command]
+async fn recommend_grid_for_rank(app: tauri::AppHandle) -> Result<Option<Grid>, AppError> {
+    let rank = get_rank(app);
+    let grids = fetch_remote_grids(&app).await?;
+    let newest = |candidates: Vec<&Grid>| -> Option<Grid> {
+        candidates
+            .into_iter()
+            .max_by_key(|grid| grid.parsed_date)
+            .cloned()
+    };
+
+    if let Some(rank) = rank.as_deref() {
+        let for_rank: Vec<&Grid> = grids
+            .iter()
+            .filter(|grid| parse_grid_rank(&grid.name) == Some(rank))
+            .collect();
+        let d2pt_for_rank: Vec<&Grid> = for_rank
+            .iter()
+            .copied()
+            .filter(|grid| classify_grid_type(&grid.name) == "d2pt")
+            .collect();
+        if let Some(grid) = newest(d2pt_for_rank).or_else(|| newest(for_rank)) {
+            return Ok(Some(grid));
+        }
+    }
+
+    Ok(newest(
+        grids
+            .iter()
+            .filter(|grid| {
+                classify_grid_type(&grid.name) == "d2pt" && parse_grid_rank(&grid.name).is_none()
+            })
+            .collect(),
+    ))
+}
+
+const BACKUP_PREFIX: &str = "hero_grid_config.backup-";
+const MAX_HISTORY_ENTRIES: usize = 1000;
+const DEFAULT_MAX_BACKUPS: usize = 5;
+
+/// Copies the current grid to hero_grid_config.backup-<unix millis>.json in
+/// the same cfg directory. Returns `None` when there is no grid to back up.
+fn backup_grid_file(cfg_dir: &Path) -> Result<Option<PathBuf>, String> {
+    let grid_file_path = cfg_dir.join(GRID_FILE_NAME);
+    if !grid_file_path.exists() {
+        return Ok(None);
+    }
+    let millis = SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let backup_path = cfg_dir.join(format!("{}{}.json", BACKUP_PREFIX, millis));
+    fs::copy(&grid_file_path, &backup_path)
+        .map_err(|e| format!("Failed to back up grid: {}", e))?;
+    Ok(Some(backup_path))
+}
+
+/// Backups in `cfg_dir` as (path, timestamp, size), oldest first.
+fn list_backup_files(cfg_dir: &Path) -> Vec<(PathBuf, u128, u64)> {
+    let mut backups: Vec<(PathBuf, u128, u64)> = fs::read_dir(cfg_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    let timestamp = name
+                        .strip_prefix(BACKUP_PREFIX)?
+                        .strip_suffix(".json")?
+                        .parse::<u128>()
+                        .ok()?;
+                    let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                    Some((entry.path(), timestamp, size))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    backups.sort_by_key(|(_, timestamp, _)| *timestamp);
+    backups
+}
+
+/// Deletes the oldest backups in `cfg_dir` until both the count limit and
+/// the total size limit are respected, whichever is stricter.
+fn prune_backups(app: &tauri::AppHandle, cfg_dir: &Path) -> PruneReport {
+    let max_count = get_max_backups(app.clone());
+    let max_bytes = get_max_backups_size_mb(app.clone()) * 1024 * 1024;
+    let mut backups = list_backup_files(cfg_dir);
+    let mut total_bytes: u64 = backups.iter().map(|(_, _, size)| size).sum();
+    let mut report = PruneReport::default();
+
+    while !backups.is_empty()
+        && (backups.len() > max_count || (max_bytes > 0 && total_bytes > max_bytes))
+    {
+        let (path, _, size) = backups.remove(0);
+        match fs::remove_file(&path) {
+            Ok(_) => {
+                report.files_removed += 1;
+                report.bytes_removed += size;
+            }
+            Err(e) => warn!("Failed to prune backup {:?}: {}", path, e),
+        }
+        total_bytes -= size;
+    }
+    if report.files_removed > 0 {
+        info!(
+            "Pruned {} backup(s) ({} bytes) in {:?}",
+            report.files_removed, report.bytes_removed, cfg_dir
+        );
+    }
+    report
+}
+
+#[tauri::command]
+fn get_max_backups(app: tauri::AppHandle) -> usize {
+    read_setting(&app, "max_backups").unwrap_or(DEFAULT_MAX_BACKUPS)
+}
+
+#[tauri::command]
+fn set_max_backups(app: tauri::AppHandle, count: usize) -> Result<(), AppError> {
+    info!("Setting max_backups to: {}", count);
+    write_setting(&app, "max_backups", count).map_err(AppError::IoError)
+}
+
+#[tauri::command]
+fn get_max_backups_size_mb(app: tauri::AppHandle) -> u64 {
+    read_setting(&app, "max_backups_size_mb").unwrap_or(0)
+}
+
+/// Caps the total size of the backups kept per cfg directory; 0 disables the
+/// size limit and leaves only the count limit.
+#[tauri::command]
+fn set_max_backups_size_mb(app: tauri::AppHandle, size_mb: u64) -> Result<(), AppError> {
+    info!("Setting max_backups_size_mb to: {}", size_mb);
+    write_setting(&app, "max_backups_size_mb", size_mb).map_err(AppError::IoError)
+}
+
+#[tauri::command]
+fn prune_grid_backups(
+    app: tauri::AppHandle,
+    dota_config_path: Option<String>,
+) -> Result<PruneReport, AppError> {
+    let config_dir =
+        resolve_config_dir(&app, dota_config_path).map_err(AppError::ConfigNotFound)?;
+    Ok(prune_backups(&app, &config_dir))
+}
+
+/// Validates a backup and writes it back over the hero_grid_config.json next
+/// to it. Returns the hash of the restored grid.
+fn restore_backup_file(backup_path: &Path) -> Result<String, String> {
+    let content = fs::read(backup_path)
+        .map_err(|e| format!("Failed to read backup {:?}: {}", backup_path, e))?;
+    parse_grid_config(&content)
+        .map_err(|e| format!("Backup {:?} is not a valid grid: {}", backup_path, e))?;
+    let cfg_dir = backup_path
+        .parent()
+        .ok_or_else(|| format!("Invalid backup path: {:?}", backup_path))?;
+    write_file_atomic(&cfg_dir.join(GRID_FILE_NAME), &content)?;
+    Ok(format!("{:x}", md5::compute(&content)))
+}
+
+/// Emits "grid-applied" after a restore from `backup_path`. The backup's
+/// filename stands in for the grid name, and its type comes out "unknown".
+fn emit_grid_restored(app: &tauri::AppHandle, backup_path: &Path, hash: &str, backup_made: bool) {
+    let Some(cfg_dir) = backup_path.parent() else {
+        return;
+    };
+    let name = backup_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    emit_grid_applied(
+        app,
+        &cfg_dir.join(GRID_FILE_NAME),
+        &name,
+        &name,
+        hash,
+        backup_made,
+    );
+}
+
+/// Lists the grid backups in the cfg directory, newest first.
+#[tauri::command]
+fn list_grid_backups(
+    app: tauri::AppHandle,
+    dota_config_path: Option<String>,
+) -> Result<Vec<GridBackup>, AppError> {
+    let config_dir =
+        resolve_config_dir(&app, dota_config_path).map_err(AppError::ConfigNotFound)?;
+    Ok(list_backup_files(&config_dir)
+        .into_iter()
+        .rev()
+        .map(|(path, timestamp, size)| GridBackup {
+            name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            created_at: timestamp as u64,
+            size_bytes: size,
+        })
+        .collect())
+}
+
+/// Restores a backup listed by list_grid_backups. The grid it replaces is
+/// backed up first, so a restore can itself be undone.
+#[tauri::command]
+fn restore_grid_backup(
+    app: tauri::AppHandle,
+    backup_name: String,
+    dota_config_path: Option<String>,
+) -> Result<(), AppError> {
+    let config_dir =
+        resolve_config_dir(&app, dota_config_path).map_err(AppError::ConfigNotFound)?;
+    let backup_path = list_backup_files(&config_dir)
+        .into_iter()
+        .map(|(path, _, _)| path)
+        .find(|path| {
+            path.file_name()
+                .is_some_and(|name| name == backup_name.as_str())
+        })
+        .ok_or_else(|| {
+            AppError::Other(format!(
+                "No backup named {} in {:?}",
+                backup_name, config_dir
+            ))
+        })?;
+
+    info!("Restoring {:?}", backup_path);
+    let backup_made = backup_grid_file(&config_dir)
+        .map_err(AppError::IoError)?
+        .is_some();
+    let hash = restore_backup_file(&backup_path).map_err(AppError::IoError)?;
+    prune_backups(&app, &config_dir);
+    emit_grid_restored(&app, &backup_path, &hash, backup_made);
+    Ok(())
+}
+
+/// Backs up the grid about to be replaced and remembers the backup so the
+/// activation can be undone. Returns whether there was a grid to back up.
+fn remember_pre_activation_backup(app: &tauri::AppHandle, cfg_dir: &Path) -> Result<bool, String> {
+    let Some(backup_path) = backup_grid_file(cfg_dir)? else {
+        return Ok(false);
+    };
+    let pointer = backup_path.to_string_lossy().to_string();
+    if let Err(e) = write_setting(app, "pre_activation_backup", pointer) {
+        warn!("Failed to remember pre-activation backup: {}", e);
+    }
+    prune_backups(app, cfg_dir);
+    Ok(true)
+}
+
+/// Emits "grid-applied" so the frontend can refresh its status the same way
+/// whichever command wrote the grid. The type comes from the source filename.
+fn emit_grid_applied(
+    app: &tauri::AppHandle,
+    config_path: &Path,
+    name: &str,
+    download_url: &str,
+    hash: &str,
+    backup_made: bool,
+) {
+    let filename = download_url.rsplit('/').next().unwrap_or(download_url);
+    let event = GridAppliedEvent {
+        name: name.to_string(),
+        grid_type: classify_grid_type(filename).to_string(),
+        hash: hash.to_string(),
+        backup_made,
+        config_path: config_path.to_string_lossy().to_string(),
+    };
+    if let Err(e) = app.emit("grid-applied", &event) {
+        warn!("Failed to emit grid-applied: {}", e);
+    }
+    refresh_tray_status(app);
+}
+
+/// The backup taken before the last activation, if it still exists.
+fn pre_activation_backup(app: &tauri::AppHandle) -> Option<PathBuf> {
+    read_setting::<String>(app, "pre_activation_backup")
+        .map(PathBuf::from)
+        .filter(|path| path.is_file())
+}
+
+/// Shows what undo_last_activation would restore without applying it.
+#[tauri::command]
+fn preview_undo(app: tauri::AppHandle) -> Result<Option<UndoPreview>, AppError> {
+    let Some(backup_path) = pre_activation_backup(&app) else {
+        return Ok(None);
+    };
+    let content = fs::read(&backup_path).map_err(|e| {
+        AppError::IoError(format!("Failed to read backup {:?}: {}", backup_path, e))
+    })?;
+    let grid = parse_grid_config(&content)
+        .map_err(|e| format!("Backup {:?} is not a valid grid: {}", backup_path, e))?;
+    Ok(Some(UndoPreview {
+        backup_path: backup_path.to_string_lossy().to_string(),
+        size_bytes: content.len() as u64,
+        configs: summarize_configs(&grid),
+    }))
+}
+
+/// Restores the grid that was installed before the last activation.
+#[tauri::command]
+fn undo_last_activation(app: tauri::AppHandle) -> Result<String, AppError> {
+    let backup_path = pre_activation_backup(&app)
+        .ok_or_else(|| AppError::Other("There is nothing to undo".to_string()))?;
+    let hash = restore_backup_file(&backup_path).map_err(AppError::IoError)?;
+    emit_grid_restored(&app, &backup_path, &hash, false);
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| AppError::IoError(format!("Failed to open settings store: {}", e)))?;
+    store.delete("pre_activation_backup");
+    store
+        .save()
+        .map_err(|e| AppError::IoError(format!("Failed to save settings: {}", e)))?;
+    Ok(backup_path.to_string_lossy().to_string())
+}
+
+/// Backs up the grid if it changed since the last scheduled backup. Returns
+/// the new backup, or None when the grid is unchanged or missing.
+fn run_auto_backup(app: &tauri::AppHandle) -> Result<Option<PathBuf>, String> {
+    let cfg_dir = resolve_config_dir(app, None)?;
+    let grid_file_path = cfg_dir.join(GRID_FILE_NAME);
+    if !grid_file_path.exists() {
+        return Ok(None);
+    }
+    let hash = hash_file(&grid_file_path)?;
+
+    let mut status: AutoBackupStatus = read_setting(app, "auto_backup_status").unwrap_or_default();
+    if status.last_hash.as_deref() == Some(hash.as_str()) {
+        return Ok(None);
+    }
+    let Some(backup_path) = backup_grid_file(&cfg_dir)? else {
+        return Ok(None);
+    };
+    prune_backups(app, &cfg_dir);
+
+    status.last_backup_at = Some(unix_now());
+    status.last_backup_path = Some(backup_path.to_string_lossy().to_string());
+    status.last_hash = Some(hash);
+    write_setting(app, "auto_backup_status", status)?;
+    Ok(Some(backup_path))
+}
+
+/// Starts the backup loop. A loop exits once a newer one is started or
+/// backups are stopped, so there's never more than one running.
+fn spawn_auto_backup(app: tauri::AppHandle, interval_hours: u64) {
+    let generation = app
+        .state::<BackupScheduler>()
+        .generation
+        .fetch_add(1, AtomicOrdering::SeqCst)
+        + 1;
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let current = app
+                .state::<BackupScheduler>()
+                .generation
+                .load(AtomicOrdering::SeqCst);
+            if current != generation {
+                break;
+            }
+            match run_auto_backup(&app) {
+                Ok(Some(path)) => info!("Scheduled backup created: {:?}", path),
+                Ok(None) => info!("Grid unchanged, skipping scheduled backup"),
+                Err(e) => error!("Scheduled backup failed: {}", e),
+            }
+            tokio::time::sleep(Duration::from_secs(interval_hours * 3600)).await;
+        }
+    });
+}
+
+/// Backs up the grid every `interval_hours` while the app runs, skipping runs
+/// where the grid hasn't changed. The schedule survives restarts.
+#[tauri::command]
+fn start_auto_backup(app: tauri::AppHandle, interval_hours: u64) -> Result<(), AppError> {
+    if interval_hours == 0 {
+        return Err(AppError::Other(
+            "Backup interval must be at least one hour".to_string(),
+        ));
+    }
+    info!("Setting auto_backup_interval_hours to: {}", interval_hours);
+    write_setting(&app, "auto_backup_interval_hours", interval_hours).map_err(AppError::IoError)?;
+    spawn_auto_backup(app, interval_hours);
+    Ok(())
+}
+
+#[tauri::command]
+fn stop_auto_backup(app: tauri::AppHandle) -> Result<(), AppError> {
+    app.state::<BackupScheduler>()
+        .generation
+        .fetch_add(1, AtomicOrdering::SeqCst);
+    write_setting(&app, "auto_backup_interval_hours", 0u64).map_err(AppError::IoError)
+}
+
+#[tauri::command]
+fn get_auto_backup_status(app: tauri::AppHandle) -> AutoBackupStatus {
+    let mut status: AutoBackupStatus = read_setting(&app, "auto_backup_status").unwrap_or_default();
+    status.interval_hours = read_setting(&app, "auto_backup_interval_hours").unwrap_or(0);
+    status
+}
+
+/// Cleans up after a crash during a grid write, for every profile: a
+/// leftover hero_grid_config.json.tmp is moved into place if it's a valid
+/// grid and the real file is missing or corrupt, and discarded otherwise. A
+/// corrupt grid with no usable temp file is replaced by the newest valid
+/// backup. Each recovery is logged.
+fn reconcile_pending_writes() {
+    let cfg_dirs = match list_profile_cfg_dirs() {
+        Ok(dirs) => dirs,
+        Err(e) => {
+            error!("Failed to list profiles for write recovery: {}", e);
+            return;
+        }
+    };
+    let is_valid = |path: &Path| {
+        fs::read(path)
+            .ok()
+            .is_some_and(|content| parse_grid_config(&content).is_ok())
+    };
+
+    for (_, cfg_dir) in cfg_dirs {
+        let grid_file_path = cfg_dir.join(GRID_FILE_NAME);
+        let tmp_path = cfg_dir.join(format!("{}.tmp", GRID_FILE_NAME));
+        let grid_missing = !grid_file_path.exists();
+        let grid_corrupt = !grid_missing && !is_valid(&grid_file_path);
+
+        if tmp_path.exists() {
+            if (grid_missing || grid_corrupt)
+                && is_valid(&tmp_path)
+                && fs::rename(&tmp_path, &grid_file_path).is_ok()
+            {
+                info!(
+                    "Write recovery: completed interrupted write of {:?}",
+                    grid_file_path
+                );
+                continue;
+            }
+            match fs::remove_file(&tmp_path) {
+                Ok(()) => info!("Write recovery: removed stale temp file {:?}", tmp_path),
+                Err(e) => warn!("Failed to remove {:?}: {}", tmp_path, e),
+            }
+        }
+
+        if grid_corrupt {
+            let restored = list_backup_files(&cfg_dir)
+                .into_iter()
+                .rev()
+                .find(|(backup_path, _, _)| restore_backup_file(backup_path).is_ok());
+            match restored {
+                Some((backup_path, _, _)) => info!(
+                    "Write recovery: restored corrupt {:?} from {:?}",
+                    grid_file_path, backup_path
+                ),
+                None => error!("{:?} is corrupt and no valid backup exists", grid_file_path),
+            }
+        }
+    }
+}
+
+fn backup_sessions_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("backup_sessions.json"))
+        .map_err(|e| format!("Could not get app data directory: {}", e))
+}
+
+fn read_backup_sessions(app: &tauri::AppHandle) -> Vec<BackupSession> {
+    backup_sessions_path(app)
+        .ok()
+        .and_then(|path| fs::read(path).ok())
+        .and_then(|content| serde_json::from_slice(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_backup_sessions(app: &tauri::AppHandle, sessions: &[BackupSession]) -> Result<(), String> {
+    let path = backup_sessions_path(app)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_vec_pretty(sessions).map_err(|e| e.to_string())?;
+    write_file_atomic(&path, &content)
+}
+
+/// Backs up the grid of the resolved profile (or of every profile with
+/// `all_users`) and records the backups as one named session.
+#[tauri::command]
+fn create_backup_session(
+    app: tauri::AppHandle,
+    name: String,
+    all_users: bool,
+    dota_config_path: Option<String>,
+) -> Result<BackupSession, AppError> {
+    let cfg_dirs: Vec<PathBuf> = if all_users {
+        list_profile_cfg_dirs()
+            .map_err(AppError::IoError)?
+            .into_iter()
+            .map(|(_, dir)| dir)
+            .collect()
+    } else {
+        vec![resolve_config_dir(&app, dota_config_path).map_err(AppError::ConfigNotFound)?]
+    };
+
+    let mut backups = Vec::new();
+    for cfg_dir in &cfg_dirs {
+        if let Some(backup_path) = backup_grid_file(cfg_dir).map_err(AppError::IoError)? {
+            backups.push(backup_path.to_string_lossy().to_string());
+            prune_backups(&app, cfg_dir);
+        }
+    }
+    if backups.is_empty() {
+        return Err(AppError::ConfigNotFound(
+            "There is no grid to back up".to_string(),
+        ));
+    }
+
+    let created_at = unix_now();
+    let mut sessions = read_backup_sessions(&app);
+    let session = BackupSession {
+        id: format!("{}-{}", created_at, sessions.len() + 1),
+        name,
+        created_at,
+        backups,
+    };
+    sessions.push(session.clone());
+    write_backup_sessions(&app, &sessions).map_err(AppError::IoError)?;
+    Ok(session)
+}
+
+#[tauri::command]
+fn list_backup_sessions(app: tauri::AppHandle) -> Vec<BackupSession> {
+    let mut sessions = read_backup_sessions(&app);
+    sessions.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    sessions
+}
+
+/// Restores every backup in a session. All backups are validated first so a
+/// bad file doesn't leave the session half restored.
+#[tauri::command]
+fn restore_session(app: tauri::AppHandle, id: String) -> Result<usize, AppError> {
+    let session = read_backup_sessions(&app)
+        .into_iter()
+        .find(|session| session.id == id)
+        .ok_or_else(|| AppError::Other(format!("Backup session {} not found", id)))?;
+
+    for backup in &session.backups {
+        let content = fs::read(backup)
+            .map_err(|e| AppError::IoError(format!("Failed to read backup {}: {}", backup, e)))?;
+        parse_grid_config(&content)
+            .map_err(|e| format!("Backup {} is not a valid grid: {}", backup, e))?;
+    }
+    for backup in &session.backups {
+        let hash = restore_backup_file(Path::new(backup)).map_err(AppError::IoError)?;
+        emit_grid_restored(&app, Path::new(backup), &hash, false);
+    }
+    Ok(session.backups.len())
+}
+
+fn snapshots_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("snapshots"))
+        .map_err(|e| format!("Could not get app data directory: {}", e))
+}
+
+fn read_snapshot_set(set_dir: &Path) -> Result<SnapshotSet, String> {
+    let manifest = fs::read(set_dir.join("snapshot.json"))
+        .map_err(|e| format!("Failed to read snapshot manifest: {}", e))?;
+    serde_json::from_slice(&manifest).map_err(|e| format!("Invalid snapshot manifest: {}", e))
+}
+
+/// Copies every Steam profile's grid into one labeled snapshot set. The set
+/// is assembled in a staging directory and only renamed into place once all
+/// copies succeeded, so a failed snapshot leaves nothing behind.
+#[tauri::command]
+fn snapshot_all_users(
+    app: tauri::AppHandle,
+    label: Option<String>,
+) -> Result<SnapshotSet, AppError> {
+    let created_at = unix_now();
+    let label = label
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty());
+    let id = match &label {
+        Some(label) => {
+            let slug: String = label
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+                .collect();
+            format!("{}-{}", created_at, slug)
+        }
+        None => created_at.to_string(),
+    };
+
+    let snapshots_dir = snapshots_dir(&app).map_err(AppError::IoError)?;
+    let staging_dir = snapshots_dir.join(format!(".{}.partial", id));
+    let final_dir = snapshots_dir.join(&id);
+    if final_dir.exists() {
+        return Err(AppError::Other(format!("Snapshot {} already exists", id)));
+    }
+
+    let result = (|| -> Result<SnapshotSet, AppError> {
+        fs::create_dir_all(&staging_dir).map_err(|e| {
+            AppError::IoError(format!("Failed to create snapshot directory: {}", e))
+        })?;
+
+        let mut entries = Vec::new();
+        for (user_id, cfg_dir) in list_profile_cfg_dirs().map_err(AppError::IoError)? {
+            let grid_file_path = cfg_dir.join(GRID_FILE_NAME);
+            if !grid_file_path.exists() {
+                continue;
+            }
+            let content = fs::read(&grid_file_path).map_err(|e| {
+                AppError::IoError(format!("Failed to read grid for user {}: {}", user_id, e))
+            })?;
+            fs::write(staging_dir.join(format!("{}.json", user_id)), &content).map_err(|e| {
+                AppError::IoError(format!(
+                    "Failed to snapshot grid for user {}: {}",
+                    user_id, e
+                ))
+            })?;
+            entries.push(SnapshotEntry {
+                user_id,
+                config_path: cfg_dir.to_string_lossy().to_string(),
+                hash: format!("{:x}", md5::compute(&content)),
+            });
+        }
+        if entries.is_empty() {
+            return Err(AppError::ConfigNotFound(
+                "No Steam profile has a grid to snapshot".to_string(),
+            ));
+        }
+
+        let set = SnapshotSet {
+            id: id.clone(),
+            label,
+            created_at,
+            entries,
+        };
+        let manifest = serde_json::to_vec_pretty(&set).map_err(|e| e.to_string())?;
+        write_file_atomic(&staging_dir.join("snapshot.json"), &manifest)
+            .map_err(AppError::IoError)?;
+        fs::rename(&staging_dir, &final_dir)
+            .map_err(|e| AppError::IoError(format!("Failed to finalize snapshot: {}", e)))?;
+        Ok(set)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_dir_all(&staging_dir);
+    }
+    result
+}
+
+#[tauri::command]
+fn list_snapshot_sets(app: tauri::AppHandle) -> Result<Vec<SnapshotSet>, AppError> {
+    let snapshots_dir = snapshots_dir(&app).map_err(AppError::IoError)?;
+    let entries = match fs::read_dir(&snapshots_dir) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(Vec::new()),
+    };
+
+    let mut sets: Vec<SnapshotSet> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|entry| read_snapshot_set(&entry.path()).ok())
+        .collect();
+    sets.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(sets)
+}
+
+/// Restores every grid in a snapshot set to the profile it was taken from.
+/// Returns the number of profiles restored.
+#[tauri::command]
+fn restore_all_users(app: tauri::AppHandle, snapshot_id: String) -> Result<usize, AppError> {
+    let set_dir = snapshots_dir(&app)
+        .map_err(AppError::IoError)?
+        .join(&snapshot_id);
+    let set = read_snapshot_set(&set_dir).map_err(AppError::IoError)?;
+
+    // Read and check everything up front so a bad file doesn't leave the
+    // profiles half restored.
+    let mut restores = Vec::new();
+    for entry in &set.entries {
+        let content = fs::read(set_dir.join(format!("{}.json", entry.user_id))).map_err(|e| {
+            AppError::IoError(format!(
+                "Failed to read snapshot for user {}: {}",
+                entry.user_id, e
+            ))
+        })?;
+        parse_grid_config(&content)?;
+        restores.push((PathBuf::from(&entry.config_path), content));
+    }
+
+    for (cfg_dir, content) in &restores {
+        let grid_file_path = cfg_dir.join(GRID_FILE_NAME);
+        write_file_atomic(&grid_file_path, content).map_err(AppError::IoError)?;
+        emit_grid_applied(
+            &app,
+            &grid_file_path,
+            &snapshot_id,
+            &snapshot_id,
+            &format!("{:x}", md5::compute(content)),
+            false,
+        );
+    }
+    info!(
+        "Restored snapshot {} to {} profile(s)",
+        snapshot_id,
+        restores.len()
+    );
+    Ok(restores.len())
+}
+
+#[tauri::command]
+fn get_mirror_urls(app: tauri::AppHandle) -> Vec<String> {
+    read_setting(&app, "mirror_urls").unwrap_or_default()
+}
+
+/// Sets the mirror base URLs tried before GitHub, in order. Each one must
+/// mirror the repo root, e.g. https://cdn.jsdelivr.net/gh/abnersajr/d2pt-grid-updater@main
+#[tauri::command]
+fn set_mirror_urls(app: tauri::AppHandle, urls: Vec<String>) -> Result<(), AppError> {
+    let urls: Vec<String> = urls
+        .into_iter()
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty())
+        .collect();
+    if let Some(invalid) = urls.iter().find(|url| !url.starts_with("https://")) {
+        return Err(AppError::Other(format!(
+            "Mirror URL must use https: {}",
+            invalid
+        )));
+    }
+    info!("Setting mirror_urls to: {:?}", urls);
+    write_setting(&app, "mirror_urls", urls).map_err(AppError::IoError)
+}
+
+/// Whether a GitHub token is stored. The token itself is never sent back.
+#[tauri::command]
+fn has_github_token(app: tauri::AppHandle) -> bool {
+    github_token(&app).is_some()
+}
+
+/// Stores (or clears, with `None` or a blank string) a GitHub personal access
+/// token used for the grid listing and hash list, raising the API rate limit.
+#[tauri::command]
+fn set_github_token(app: tauri::AppHandle, token: Option<String>) -> Result<(), AppError> {
+    let token = token
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty());
+    info!(
+        "Setting github_token: {}",
+        if token.is_some() { "set" } else { "cleared" }
+    );
+    write_github_token(&app, token.as_deref()).map_err(AppError::IoError)
+}
+
+/// Extra base URLs grids may be installed from besides the grid source and
+/// the mirrors.
+#[tauri::command]
+fn get_allowed_source_urls(app: tauri::AppHandle) -> Vec<String> {
+    read_setting(&app, "allowed_source_urls").unwrap_or_default()
+}
+
+#[tauri::command]
+fn set_allowed_source_urls(app: tauri::AppHandle, urls: Vec<String>) -> Result<(), AppError> {
+    let urls: Vec<String> = urls
+        .into_iter()
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty())
+        .collect();
+    if let Some(invalid) = urls.iter().find(|url| !url.starts_with("https://")) {
+        return Err(AppError::Other(format!(
+            "Allowed source URL must use https: {}",
+            invalid
+        )));
+    }
+    info!("Setting allowed_source_urls to: {:?}", urls);
+    write_setting(&app, "allowed_source_urls", urls).map_err(AppError::IoError)
+}
+
+#[tauri::command]
+fn get_bandwidth_limit_kbps(app: tauri::AppHandle) -> u64 {
+    read_setting(&app, "bandwidth_limit_kbps").unwrap_or(0)
+}
+
+/// Caps grid download speed in kilobits per second; 0 disables the cap.
+#[tauri::command]
+fn set_bandwidth_limit_kbps(app: tauri::AppHandle, kbps: u64) -> Result<(), AppError> {
+    info!("Setting bandwidth_limit_kbps to: {}", kbps);
+    write_setting(&app, "bandwidth_limit_kbps", kbps).map_err(AppError::IoError)
+}
+
+/// Shows what merging a downloaded grid into the installed config would
+/// produce, including the full resulting config. Nothing is written.
+#[tauri::command]
+async fn merge_preview(
+    app: tauri::AppHandle,
+    download_url: String,
+    dota_config_path: Option<String>,
+) -> Result<MergePreview, AppError> {
+    let config_dir =
+        resolve_config_dir(&app, dota_config_path).map_err(AppError::ConfigNotFound)?;
+    let incoming = parse_grid_config(&fetch_grid_bytes(&app, &download_url).await?)?;
+
+    let grid_file_path = config_dir.join(GRID_FILE_NAME);
+    let current = if grid_file_path.exists() {
+        fs::read(&grid_file_path)
+            .map_err(|e| format!("Failed to read grid file: {}", e))
+            .and_then(|content| parse_grid_config(&content))
+            .map(Some)
+    } else {
+        Ok(None)
+    };
+
+    let (existing, current_parse_error) = match current {
+        Ok(existing) => (existing, None),
+        Err(e) => (None, Some(e)),
+    };
+    // Without a usable installed config, merging into an empty one reports
+    // every incoming page as added
+    let existing = existing.unwrap_or_else(|| HeroGridConfig {
+        configs: Vec::new(),
+        ..incoming.clone()
+    });
+    let outcome = merge_grid_configs(&existing, &incoming);
+
+    Ok(MergePreview {
+        added: outcome.added,
+        replaced: outcome.replaced,
+        summary: summarize_configs(&outcome.config),
+        result: outcome.config,
+        current_parse_error,
+    })
+}
+
+/// Merges a downloaded grid into the installed config under `config_name`,
+/// replacing any page already using that name. A grid with several pages
+/// keeps each page's label: "<config_name> - Carry", "<config_name> - Mid"...
+/// Goes through activate_grid's checks, backup and write. Returns the names
+/// of the installed pages.
+#[tauri::command]
+async fn activate_grid_as(
+    app: tauri::AppHandle,
+    download_url: String,
+    config_name: String,
+    dota_config_path: Option<String>,
+) -> Result<Vec<String>, AppError> {
+    let config_name = config_name.trim().to_string();
+    if config_name.is_empty() {
+        return Err(AppError::Other("Config name can't be empty".to_string()));
+    }
+    let options = ActivationOptions {
+        config_name: Some(config_name.clone()),
+        ..ActivationOptions::default()
+    };
+    let activation =
+        activate_grid_with(app, config_name, download_url, dota_config_path, options).await?;
+    Ok(activation.pages)
+}
+
+/// Renames a grid's pages for activate_grid_as: a single page becomes
+/// `config_name`, several become "<config_name> - <label>".
+fn rename_grid_pages(grid: &mut HeroGridConfig, config_name: &str) -> Result<(), AppError> {
+    if grid.configs.is_empty() {
+        return Err(AppError::Other(
+            "The downloaded grid has no configs".to_string(),
+        ));
+    }
+    let single_page = grid.configs.len() == 1;
+    for page in &mut grid.configs {
+        page.config_name = if single_page {
+            config_name.to_string()
+        } else {
+            let label = page.config_name.rsplit(" - ").next().unwrap_or_default();
+            format!("{} - {}", config_name, label)
+        };
+    }
+    Ok(())
+}
+
+// Every d2pt page is named "Dota2ProTracker <patch> - <role>"
+const D2PT_PAGE_PREFIX: &str = "Dota2ProTracker ";
+
+/// Removes the d2pt pages from the installed grid, keeping any custom pages,
+/// after backing the file up. All grid types share the same page names, so
+/// with `grid_type` the pages are only removed if the installed grid is of
+/// that type (per the app's marker for the profile, or else the last
+/// activated type). Removing every page leaves a valid config with an empty
+/// `configs` array. Returns how many pages were removed.
+#[tauri::command]
+fn remove_grid(
+    app: tauri::AppHandle,
+    grid_type: Option<String>,
+    dota_config_path: Option<String>,
+) -> Result<usize, AppError> {
+    let config_dir =
+        resolve_config_dir(&app, dota_config_path).map_err(AppError::ConfigNotFound)?;
+    let grid_file_path = config_dir.join(GRID_FILE_NAME);
+    if !grid_file_path.exists() {
+        return Ok(0);
+    }
+    let content = fs::read(&grid_file_path)
+        .map_err(|e| AppError::IoError(format!("Failed to read grid file: {}", e)))?;
+    let mut grid = parse_grid_config(&content)?;
+
+    let marker_key = config_dir.to_string_lossy().to_string();
+    if let Some(wanted) = grid_type {
+        let installed = read_managed_grids(&app)
+            .get(&marker_key)
+            .map(|marker| classify_grid_type(&marker.grid_name).to_string())
+            .unwrap_or_else(|| get_remembered_grid_type(app.clone()));
+        if installed != wanted {
+            return Err(AppError::Other(format!(
+                "The installed d2pt pages are from a {} grid, not {}",
+                installed, wanted
+            )));
+        }
+    }
+
+    let before = grid.configs.len();
+    grid.configs
+        .retain(|page| !page.config_name.starts_with(D2PT_PAGE_PREFIX));
+    let removed = before - grid.configs.len();
+    if removed == 0 {
+        return Ok(0);
+    }
+
+    let backup_made = backup_grid_file(&config_dir)
+        .map_err(AppError::IoError)?
+        .is_some();
+    prune_backups(&app, &config_dir);
+    let content = serde_json::to_vec_pretty(&grid).map_err(|e| e.to_string())?;
+    write_file_atomic(&grid_file_path, &content).map_err(AppError::IoError)?;
+
+    let mut markers = read_managed_grids(&app);
+    if markers.remove(&marker_key).is_some() {
+        if let Err(e) = write_managed_grids(&app, &markers) {
+            warn!("Failed to clear managed grid marker: {}", e);
+        }
+    }
+    info!("Removed {} d2pt page(s) from {:?}", removed, grid_file_path);
+    emit_grid_applied(
+        &app,
+        &grid_file_path,
+        GRID_FILE_NAME,
+        GRID_FILE_NAME,
+        &format!("{:x}", md5::compute(&content)),
+        backup_made,
+    );
+    Ok(removed)
+}
+
+fn pinned_grid_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("pinned_grid.json"))
+        .map_err(|e| format!("Could not get app data directory: {}", e))
+}
+
+#[tauri::command]
+fn get_pinned_grid(app: tauri::AppHandle) -> Option<PinnedGrid> {
+    read_setting(&app, "pinned_grid")
+}
+
+/// Downloads a grid and keeps a copy so it can be re-applied later even if it
+/// is no longer published.
+#[tauri::command]
+async fn pin_grid(app: tauri::AppHandle, download_url: String) -> Result<PinnedGrid, AppError> {
+    let content = fetch_grid_bytes(&app, &download_url).await?;
+    parse_grid_config(&content)?;
+
+    let path = pinned_grid_path(&app).map_err(AppError::IoError)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| AppError::IoError(e.to_string()))?;
+    }
+    write_file_atomic(&path, &content).map_err(AppError::IoError)?;
+
+    let pinned = PinnedGrid {
+        name: download_url
+            .rsplit('/')
+            .next()
+            .unwrap_or(&download_url)
+            .to_string(),
+        hash: format!("{:x}", md5::compute(&content)),
+        download_url,
+        pinned_at: unix_now(),
+    };
+    write_setting(&app, "pinned_grid", &pinned).map_err(AppError::IoError)?;
+    Ok(pinned)
+}
+
+#[tauri::command]
+fn unpin_grid(app: tauri::AppHandle) -> Result<(), AppError> {
+    write_setting(&app, "pinned_grid", None::<PinnedGrid>).map_err(AppError::IoError)?;
+    let path = pinned_grid_path(&app).map_err(AppError::IoError)?;
+    if path.exists() {
+        fs::remove_file(&path)
+            .map_err(|e| AppError::IoError(format!("Failed to remove pinned grid: {}", e)))?;
+    }
+    Ok(())
+}
+
+/// Writes the pinned grid's stored copy back into the config right away,
+/// e.g. after Dota overwrote it on exit. The replaced grid is backed up so
+/// the reapply can be undone. Fails with AppError::DotaRunning while Dota 2
+/// is open unless `force` is set.
+#[tauri::command]
+fn reapply_pinned_grid(
+    app: tauri::AppHandle,
+    dota_config_path: Option<String>,
+    force: bool,
+) -> Result<PinnedGrid, AppError> {
+    if !force {
+        ensure_dota_closed()?;
+    }
+    let pinned = get_pinned_grid(app.clone())
+        .ok_or_else(|| AppError::Other("No grid is pinned".to_string()))?;
+    let content = fs::read(pinned_grid_path(&app).map_err(AppError::IoError)?)
+        .map_err(|e| AppError::IoError(format!("Failed to read pinned grid: {}", e)))?;
+    let hash = format!("{:x}", md5::compute(&content));
+    if hash != pinned.hash {
+        return Err(AppError::HashMismatch(format!(
+            "Stored pinned grid is corrupted: expected {}, got {}",
+            pinned.hash, hash
+        )));
+    }
+
+    let config_dir =
+        resolve_config_dir(&app, dota_config_path).map_err(AppError::ConfigNotFound)?;
+    let grid_file_path = config_dir.join(GRID_FILE_NAME);
+    let backup_made =
+        remember_pre_activation_backup(&app, &config_dir).map_err(AppError::IoError)?;
+    write_file_atomic(&grid_file_path, &content).map_err(AppError::IoError)?;
+    record_activation(&app, &config_dir, &hash);
+    record_history(&app, &pinned.download_url, &hash);
+    stamp_managed_marker(&app, &config_dir, &pinned.download_url, &hash);
+    emit_grid_applied(
+        &app,
+        &grid_file_path,
+        &pinned.name,
+        &pinned.download_url,
+        &hash,
+        backup_made,
+    );
+    Ok(pinned)
+}
+
+/// Lists heroes whose category differs between the installed config and a
+/// remote grid, page by page. Heroes only present on one side are skipped.
+#[tauri::command]
+async fn category_changes(
+    app: tauri::AppHandle,
+    download_url: String,
+    dota_config_path: Option<String>,
+) -> Result<Vec<CategoryChange>, AppError> {
+    let config_dir =
+        resolve_config_dir(&app, dota_config_path).map_err(AppError::ConfigNotFound)?;
+    let current_content = fs::read(config_dir.join(GRID_FILE_NAME))
+        .map_err(|e| AppError::IoError(format!("Failed to read grid file: {}", e)))?;
+    let current = parse_grid_config(&current_content)?;
+    let latest = parse_grid_config(&fetch_grid_bytes(&app, &download_url).await?)?;
+    let heroes = load_hero_names(&app).await;
+
+    let mut changes = Vec::new();
+    for new_page in &latest.configs {
+        let Some(old_page) = find_matching_page(&current, &new_page.config_name) else {
+            continue;
+        };
+        let old_categories = hero_categories(old_page);
+        let mut new_categories: Vec<(u32, &str)> = hero_categories(new_page).into_iter().collect();
+        new_categories.sort_by_key(|(hero_id, _)| *hero_id);
+
+        for (hero_id, new_category) in new_categories {
+            if let Some(old_category) = old_categories.get(&hero_id) {
+                if *old_category != new_category {
+                    changes.push(CategoryChange {
+                        config_name: new_page.config_name.clone(),
+                        hero_id,
+                        hero_name: hero_display_name(&heroes, hero_id),
+                        old_category: old_category.to_string(),
+                        new_category: new_category.to_string(),
+                    });
+                }
             }
+        }
+    }
+    Ok(changes)
+}
+
+fn category_counts(page: Option<&GridConfigPage>) -> Vec<(String, usize)> {
+    page.map(|page| {
+        page.categories
+            .iter()
+            .map(|category| (category.category_name.clone(), category.hero_ids.len()))
+            .collect()
+    })
+    .unwrap_or_default()
+}
+
+/// Compares hero counts per category between two grids (URLs or local
+/// paths), pairing pages like category_changes does. Sorted by the largest
+/// absolute change first.
+#[tauri::command]
+async fn category_count_diff(
+    app: tauri::AppHandle,
+    url_a: String,
+    url_b: String,
+) -> Result<Vec<CategoryCountDiff>, AppError> {
+    let grid_a = parse_grid_config(&load_grid_source(&app, &url_a).await?)?;
+    let grid_b = parse_grid_config(&load_grid_source(&app, &url_b).await?)?;
+
+    let mut pairs: Vec<(String, Option<&GridConfigPage>, Option<&GridConfigPage>)> = grid_a
+        .configs
+        .iter()
+        .map(|page| {
+            let matched = find_matching_page(&grid_b, &page.config_name);
+            (page.config_name.clone(), Some(page), matched)
         })
         .collect();
-    Ok(grids)
+    for page in &grid_b.configs {
+        if find_matching_page(&grid_a, &page.config_name).is_none() {
+            pairs.push((page.config_name.clone(), None, Some(page)));
+        }
+    }
+
+    let mut diffs = Vec::new();
+    for (config_name, page_a, page_b) in pairs {
+        let counts_a = category_counts(page_a);
+        let counts_b = category_counts(page_b);
+        let mut names: Vec<&String> = counts_a.iter().map(|(name, _)| name).collect();
+        for (name, _) in &counts_b {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        for name in names {
+            let find = |counts: &[(String, usize)]| {
+                counts
+                    .iter()
+                    .find(|(category, _)| category == name)
+                    .map(|(_, count)| *count)
+            };
+            let count_a = find(&counts_a);
+            let count_b = find(&counts_b);
+            diffs.push(CategoryCountDiff {
+                config_name: config_name.clone(),
+                category_name: name.clone(),
+                count_a,
+                count_b,
+                delta: count_b.unwrap_or(0) as i64 - count_a.unwrap_or(0) as i64,
+            });
+        }
+    }
+    diffs.sort_by(|a, b| b.delta.abs().cmp(&a.delta.abs()));
+    Ok(diffs)
+}
+
+/// Whether two pages hold the same categories, heroes and layout, ignoring
+/// the page name and ordering noise.
+fn same_page_content(a: &GridConfigPage, b: &GridConfigPage) -> bool {
+    let normalized = |page: &GridConfigPage| {
+        let grid = normalize_grid_config(HeroGridConfig {
+            configs: vec![GridConfigPage {
+                config_name: String::new(),
+                ..page.clone()
+            }],
+            version: None,
+            extra: serde_json::Map::new(),
+        });
+        serde_json::to_value(grid).ok()
+    };
+    normalized(a) == normalized(b)
+}
+
+/// Compares the installed grid with a remote one page by page, pairing pages
+/// like category_changes does. Without an installed grid every remote page
+/// is reported as added.
+#[tauri::command]
+async fn diff_grid(
+    app: tauri::AppHandle,
+    download_url: String,
+    dota_config_path: Option<String>,
+) -> Result<GridDiff, AppError> {
+    let config_dir =
+        resolve_config_dir(&app, dota_config_path).map_err(AppError::ConfigNotFound)?;
+    let remote = parse_grid_config(&fetch_grid_bytes(&app, &download_url).await?)?;
+    let grid_file_path = config_dir.join(GRID_FILE_NAME);
+    let installed = if grid_file_path.exists() {
+        let content = fs::read(&grid_file_path)
+            .map_err(|e| AppError::IoError(format!("Failed to read grid file: {}", e)))?;
+        parse_grid_config(&content)?
+    } else {
+        HeroGridConfig {
+            configs: Vec::new(),
+            version: None,
+            extra: serde_json::Map::new(),
+        }
+    };
+
+    let mut diff = GridDiff {
+        added: Vec::new(),
+        removed: Vec::new(),
+        changed: Vec::new(),
+        unchanged: Vec::new(),
+    };
+    let mut matched = HashSet::new();
+    for page in &remote.configs {
+        let Some(installed_page) = find_matching_page(&installed, &page.config_name) else {
+            diff.added.push(page.config_name.clone());
+            continue;
+        };
+        matched.insert(installed_page.config_name.as_str());
+        if same_page_content(installed_page, page) {
+            diff.unchanged.push(page.config_name.clone());
+            continue;
+        }
+
+        let old_categories = hero_categories(installed_page);
+        let new_categories = hero_categories(page);
+        let heroes_moved = new_categories
+            .iter()
+            .filter(|(hero_id, category)| {
+                old_categories
+                    .get(*hero_id)
+                    .is_some_and(|old| old != *category)
+            })
+            .count();
+        diff.changed.push(PageDiff {
+            config_name: page.config_name.clone(),
+            installed_name: installed_page.config_name.clone(),
+            heroes_moved,
+            heroes_added: new_categories
+                .keys()
+                .filter(|hero_id| !old_categories.contains_key(*hero_id))
+                .count(),
+            heroes_removed: old_categories
+                .keys()
+                .filter(|hero_id| !new_categories.contains_key(*hero_id))
+                .count(),
+        });
+    }
+    diff.removed = installed
+        .configs
+        .iter()
+        .filter(|page| !matched.contains(page.config_name.as_str()))
+        .map(|page| page.config_name.clone())
+        .collect();
+    Ok(diff)
+}
+
+#[tauri::command]
+fn get_cloud_retry_count(app: tauri::AppHandle) -> u32 {
+    read_setting(&app, "cloud_retry_count").unwrap_or(DEFAULT_CLOUD_RETRY_COUNT)
+}
+
+#[tauri::command]
+fn set_cloud_retry_count(app: tauri::AppHandle, retries: u32) -> Result<(), AppError> {
+    info!("Setting cloud_retry_count to: {}", retries);
+    write_setting(&app, "cloud_retry_count", retries).map_err(AppError::IoError)
+}
+
+#[tauri::command]
+fn get_write_check_window_ms(app: tauri::AppHandle) -> u64 {
+    read_setting(&app, "write_check_window_ms").unwrap_or(DEFAULT_WRITE_CHECK_WINDOW_MS)
+}
+
+/// Sets how long to wait after writing the grid before checking it was kept.
+#[tauri::command]
+fn set_write_check_window_ms(app: tauri::AppHandle, window_ms: u64) -> Result<(), AppError> {
+    if window_ms > MAX_WRITE_CHECK_WINDOW_MS {
+        return Err(AppError::Other(format!(
+            "Write check window can't exceed {} ms",
+            MAX_WRITE_CHECK_WINDOW_MS
+        )));
+    }
+    info!("Setting write_check_window_ms to: {}", window_ms);
+    write_setting(&app, "write_check_window_ms", window_ms).map_err(AppError::IoError)
+}
+
+/// Activates a grid and re-reads it after a short delay. Steam Cloud can lock
+/// or revert hero_grid_config.json while it syncs, so a reverted write is
+/// retried up to the configured count before giving up. A file that vanished
+/// or changed into something other than the previous grid points to an
+/// antivirus instead, reported as AppError::AntivirusSuspected. Refuses with
+/// AppError::DotaRunning while Dota 2 is open.
+#[tauri::command]
+async fn activate_grid_smart(
+    app: tauri::AppHandle,
+    download_url: String,
+    dota_config_path: Option<String>,
+) -> Result<SmartActivationResult, AppError> {
+    let config_dir =
+        resolve_config_dir(&app, dota_config_path).map_err(AppError::ConfigNotFound)?;
+    install_grid_with_retry(&app, &config_dir, &download_url).await
+}
+
+/// What re-reading the grid shortly after writing it found.
+enum WriteCheck {
+    Kept,
+    Reverted,    // back to the grid that was there before, e.g. Steam Cloud
+    Altered,     // something else entirely, e.g. an antivirus
+    Disappeared, // deleted or quarantined
+    Unreadable(String),
+}
+
+fn check_written_grid(
+    grid_file_path: &Path,
+    expected_hash: &str,
+    previous_hash: Option<&str>,
+) -> WriteCheck {
+    match hash_file(grid_file_path) {
+        Ok(written_hash) if written_hash == expected_hash => WriteCheck::Kept,
+        Ok(written_hash) if Some(written_hash.as_str()) == previous_hash => WriteCheck::Reverted,
+        Ok(_) => WriteCheck::Altered,
+        Err(_) if !grid_file_path.exists() => WriteCheck::Disappeared,
+        Err(e) => WriteCheck::Unreadable(e),
+    }
+}
+
+fn antivirus_suspected_error(detail: &str, attempts: u32) -> AppError {
+    AppError::AntivirusSuspected(format!(
+        "{} after {} attempt(s). An antivirus such as Windows Defender \
+         (Controlled folder access) may be blocking the app; allow it to write to the Dota 2 \
+         cfg folder and try again.",
+        detail, attempts
+    ))
+}
+
+/// Fails with AppError::DotaRunning while Dota 2 is open: it would overwrite
+/// the grid when it exits, so a write would seem to succeed and then be lost.
+fn ensure_dota_closed() -> Result<(), AppError> {
+    if dota_running().map_err(AppError::IoError)? {
+        return Err(AppError::DotaRunning(
+            "Dota 2 is running and would overwrite the grid when it exits. \
+             Close Dota first and try again."
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// The install behind activate_grid_smart, the tray, "Update to Latest" and
+/// activate_if_unmodified. None of these can force, so it always refuses
+/// while Dota is running.
+async fn install_grid_with_retry(
+    app: &tauri::AppHandle,
+    config_dir: &Path,
+    download_url: &str,
+) -> Result<SmartActivationResult, AppError> {
+    ensure_dota_closed()?;
+    check_download_source(app, download_url)?;
+    // A read-only cfg folder would otherwise fail every retry below as if
+    // Steam Cloud were reverting the file
+    check_config_writable(config_dir)?;
+    let grid_file_path = config_dir.join(GRID_FILE_NAME);
+    let name = download_url.rsplit('/').next().unwrap_or(download_url);
+    let (content, verified) = fetch_grid_bytes_verified(app, download_url).await?;
+    if !verified {
+        warn!("{} has no published hash, installing unverified", name);
+    }
+    parse_grid_config(&content)?;
+    let expected_hash = format!("{:x}", md5::compute(&content));
+    let previous_hash = hash_file(&grid_file_path).ok();
+    let backup_made = remember_pre_activation_backup(app, config_dir).map_err(AppError::IoError)?;
+    let max_retries = get_cloud_retry_count(app.clone());
+    let check_window = Duration::from_millis(get_write_check_window_ms(app.clone()));
+
+    let mut last_error = String::new();
+    let mut antivirus_suspected = false;
+    let mut reverted = false;
+    for attempt in 0..=max_retries {
+        antivirus_suspected = false;
+        let write_strategy = match write_file_atomic_with_strategy(&grid_file_path, &content) {
+            Ok(strategy) => strategy,
+            Err(e) => {
+                warn!("Grid write attempt {} failed: {}", attempt + 1, e);
+                last_error = e;
+                continue;
+            }
+        };
+        tokio::time::sleep(check_window).await;
+        match check_written_grid(&grid_file_path, &expected_hash, previous_hash.as_deref()) {
+            WriteCheck::Kept => {
+                remember_grid_type(app, download_url);
+                record_activation(app, config_dir, &expected_hash);
+                record_history(app, download_url, &expected_hash);
+                stamp_managed_marker(app, config_dir, download_url, &expected_hash);
+                emit_grid_applied(
+                    app,
+                    &grid_file_path,
+                    name,
+                    download_url,
+                    &expected_hash,
+                    backup_made,
+                );
+                let cloud_conflict = reverted;
+                let mut warnings = Vec::new();
+                if cloud_conflict {
+                    warnings.push(
+                        "The grid was reverted after writing, most likely by Steam Cloud. \
+                         It was applied again, but make sure Steam Cloud has finished syncing."
+                            .to_string(),
+                    );
+                }
+                if !verified {
+                    warnings.push(format!(
+                        "{} isn't listed in grid_hashes.txt, so the download couldn't be verified.",
+                        name
+                    ));
+                }
+                return Ok(SmartActivationResult {
+                    hash: expected_hash,
+                    attempts: attempt + 1,
+                    cloud_conflict,
+                    verified,
+                    warning: (!warnings.is_empty()).then(|| warnings.join(" ")),
+                    write_strategy: write_strategy.to_string(),
+                });
+            }
+            WriteCheck::Reverted => {
+                warn!("Grid write attempt {} was reverted", attempt + 1);
+                reverted = true;
+                last_error = "the file was reverted after writing".to_string();
+            }
+            WriteCheck::Altered => {
+                warn!("Grid write attempt {} was altered", attempt + 1);
+                antivirus_suspected = true;
+                last_error = "the file was altered right after writing".to_string();
+            }
+            WriteCheck::Disappeared => {
+                warn!("Grid write attempt {} disappeared", attempt + 1);
+                antivirus_suspected = true;
+                last_error = "the file disappeared right after writing".to_string();
+            }
+            WriteCheck::Unreadable(e) => {
+                last_error = format!("Failed to re-read grid file: {}", e);
+            }
+        }
+    }
+
+    if antivirus_suspected {
+        return Err(antivirus_suspected_error(&last_error, max_retries + 1));
+    }
+    Err(AppError::IoError(format!(
+        "Grid did not stick after {} attempt(s) ({}). Steam Cloud may be syncing \
+         hero_grid_config.json; wait for it to finish or disable Steam Cloud for Dota 2 and try again.",
+        max_retries + 1,
+        last_error
+    )))
 }
 
+/// Downloads a grid and installs it as hero_grid_config.json in the given cfg
+/// directory (or the detected one). The download must match its entry in
+/// grid_hashes.txt; grids without one are installed but flagged unverified.
+/// The written file is read back and hashed. While Dota 2 runs it would
+/// overwrite the grid on exit, so this fails with AppError::DotaRunning
+/// unless `force` is set. With `merge`, the downloaded pages replace installed
+/// pages of the same `config_name` and any other (custom) pages are kept;
+/// otherwise the whole file is replaced. With `dry_run`, the download and
+/// validation run the same way, but the Dota-running and writability checks
+/// are skipped, no backup is taken, nothing is written, and the result
+/// describes what would have been installed.
 #[tauri::command]
-async fn download_grid_hashes() -> Result<GridHashes, String> {
-    let client = reqwest::Client::new();
-    let url = "https://raw.githubusercontent.com/abnersajr/d2pt-grid-updater/main/grid_hashes.txt";
+async fn activate_grid(
+    app: tauri::AppHandle,
+    grid_name: String,
+    download_url: String,
+    dota_config_path: Option<String>,
+    force: bool,
+    merge: bool,
+    dry_run: bool,
+) -> Result<GridActivation, AppError> {
+    let options = ActivationOptions {
+        force,
+        merge,
+        dry_run,
+        config_name: None,
+    };
+    activate_grid_with(app, grid_name, download_url, dota_config_path, options).await
+}
+
+/// The flags of activate_grid, so Rust callers don't pass bare bools.
+/// `config_name` renames the downloaded pages (see activate_grid_as) and
+/// implies `merge`.
+#[derive(Debug, Clone, Default)]
+struct ActivationOptions {
+    force: bool,
+    merge: bool,
+    dry_run: bool,
+    config_name: Option<String>,
+}
+
+async fn activate_grid_with(
+    app: tauri::AppHandle,
+    grid_name: String,
+    download_url: String,
+    dota_config_path: Option<String>,
+    options: ActivationOptions,
+) -> Result<GridActivation, AppError> {
+    let ActivationOptions {
+        force,
+        merge,
+        dry_run,
+        config_name,
+    } = options;
+    let merge = merge || config_name.is_some();
+    info!(
+        "Activating {} from {}{}",
+        grid_name,
+        download_url,
+        if dry_run { " (dry run)" } else { "" }
+    );
+    // Dota only matters once the file is written
+    if !force && !dry_run {
+        ensure_dota_closed()?;
+    }
+    check_download_source(&app, &download_url)?;
+    let config_dir =
+        resolve_config_dir(&app, dota_config_path).map_err(AppError::ConfigNotFound)?;
+    // Fail before the download rather than after it. The check writes a probe
+    // file, so a dry run skips it to leave the directory untouched.
+    if !dry_run {
+        check_config_writable(&config_dir)?;
+    }
+    let (content, verified) = fetch_grid_bytes_verified(&app, &download_url).await?;
+    if !verified {
+        warn!("{} has no published hash, installing unverified", grid_name);
+    }
+    let mut incoming = parse_grid_config(&content)?;
+    if let Some(config_name) = &config_name {
+        rename_grid_pages(&mut incoming, config_name)?;
+    }
+    let pages: Vec<String> = incoming
+        .configs
+        .iter()
+        .map(|page| page.config_name.clone())
+        .collect();
+
+    let grid_file_path = config_dir.join(GRID_FILE_NAME);
+    let content = if merge && grid_file_path.exists() {
+        let installed =
+            fs::read(&grid_file_path).map_err(|e| format!("Failed to read grid file: {}", e))?;
+        let existing = parse_grid_config(&installed)
+            .map_err(|e| format!("Installed grid can't be merged into: {}", e))?;
+        let outcome = merge_grid_configs(&existing, &incoming);
+        info!(
+            "Merging {}: {} page(s) added, {} replaced",
+            grid_name,
+            outcome.added.len(),
+            outcome.replaced.len()
+        );
+        serde_json::to_vec_pretty(&outcome.config).map_err(|e| e.to_string())?
+    } else if config_name.is_some() {
+        serde_json::to_vec_pretty(&incoming).map_err(|e| e.to_string())?
+    } else {
+        content
+    };
+    let warning = (!verified).then(|| {
+        format!(
+            "{} isn't listed in grid_hashes.txt, so the download couldn't be verified",
+            grid_name
+        )
+    });
 
-    let response = client
-        .get(url)
-        .header("User-Agent", "d2pt-grid-updater-app")
-        .send()
-        .await
-        .map_err(|e| format!("Failed to download grid hashes: {}", e))?;
+    let expected_hash = format!("{:x}", md5::compute(&content));
 
-    if !response.status().is_success() {
-        return Err(format!(
-            "Failed to download grid hashes: {}",
-            response.status()
-        ));
+    if dry_run {
+        let value: serde_json::Value =
+            serde_json::from_slice(&content).map_err(|e| e.to_string())?;
+        let validation = GridValidation {
+            hash: Some(expected_hash.clone()),
+            ..validate_grid_value(&value)
+        };
+        return Ok(GridActivation {
+            hash: expected_hash,
+            verified,
+            warning,
+            dry_run: Some(DryRunReport {
+                target_path: grid_file_path.to_string_lossy().to_string(),
+                would_overwrite: grid_file_path.exists(),
+                validation,
+            }),
+            pages,
+        });
     }
 
-    let content = response.text().await.map_err(|e| e.to_string())?;
-    let mut hashes = HashMap::new();
+    let previous_hash = hash_file(&grid_file_path).ok();
+    let backup_made =
+        remember_pre_activation_backup(&app, &config_dir).map_err(AppError::IoError)?;
+    write_file_atomic(&grid_file_path, &content).map_err(AppError::IoError)?;
 
-    for line in content.lines() {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
+    // Same check activate_grid_smart makes, without its retries
+    tokio::time::sleep(Duration::from_millis(get_write_check_window_ms(
+        app.clone(),
+    )))
+    .await;
+    match check_written_grid(&grid_file_path, &expected_hash, previous_hash.as_deref()) {
+        WriteCheck::Kept => {}
+        WriteCheck::Reverted => {
+            return Err(AppError::IoError(
+                "The grid was reverted right after writing, most likely by Steam Cloud. \
+                 Wait for it to finish syncing and try again."
+                    .to_string(),
+            ))
         }
-        if let Some((filename, hash)) = line.split_once(',') {
-            hashes.insert(filename.to_string(), hash.to_string());
+        WriteCheck::Altered => {
+            return Err(antivirus_suspected_error(
+                "the file was altered right after writing",
+                1,
+            ))
+        }
+        WriteCheck::Disappeared => {
+            return Err(antivirus_suspected_error(
+                "the file disappeared right after writing",
+                1,
+            ))
+        }
+        WriteCheck::Unreadable(e) => {
+            return Err(AppError::IoError(format!(
+                "Failed to re-read grid file: {}",
+                e
+            )))
         }
     }
+    let hash = expected_hash;
 
-    Ok(GridHashes { hashes })
+    remember_grid_type(&app, &download_url);
+    record_activation(&app, &config_dir, &hash);
+    record_history(&app, &download_url, &hash);
+    stamp_managed_marker(&app, &config_dir, &download_url, &hash);
+    emit_grid_applied(
+        &app,
+        &grid_file_path,
+        &grid_name,
+        &download_url,
+        &hash,
+        backup_made,
+    );
+    Ok(GridActivation {
+        hash,
+        verified,
+        warning,
+        dry_run: None,
+        pages,
+    })
 }
 
+// Files in the app data directory that only hold downloaded data and are
+// rebuilt on demand. Settings, history, pins and markers are not listed.
+const CACHE_FILES: &[&str] = &[
+    "grid_list_cache.json",
+    "heroes.json",
+    "source_info_cache.json",
+];
+
+/// Deletes the download caches (grid list and its ETag, hero metadata,
+/// README) and forgets the detected cfg directory. Settings and the
+/// installed grid are never touched. With `purge_backups`, grid backups in
+/// every profile are deleted too, except the newest one per profile so the
+/// last activation can still be undone.
 #[tauri::command]
-fn detect_current_grid(dota_config_path: Option<String>) -> Result<Option<DetectedGrid>, String> {
-    let config_path = match dota_config_path {
-        Some(path) => PathBuf::from(path),
-        None => {
-            let found_path = find_dota_config_path()?;
-            match found_path {
-                Some(path) => path,
-                None => return Ok(None),
+fn clear_cache(app: tauri::AppHandle, purge_backups: bool) -> Result<PruneReport, AppError> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::IoError(format!("Could not get app data directory: {}", e)))?;
+    let mut report = PruneReport::default();
+    let mut remove = |path: &Path| {
+        let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        match fs::remove_file(path) {
+            Ok(_) => {
+                report.files_removed += 1;
+                report.bytes_removed += size;
             }
+            Err(e) => warn!("Failed to remove {:?}: {}", path, e),
         }
     };
 
-    let grid_file_path = config_path.join("hero_grid_config.json");
-    if !grid_file_path.exists() {
-        return Ok(None);
+    for name in CACHE_FILES {
+        let path = app_data_dir.join(name);
+        if path.exists() {
+            remove(&path);
+        }
     }
+    *app.state::<DetectionCache>().config_path.lock().unwrap() = None;
 
-    let content =
-        fs::read(&grid_file_path).map_err(|e| format!("Failed to read grid file: {}", e))?;
-    let hash = format!("{:x}", md5::compute(&content));
-
-    // Try to determine grid type from filename patterns in the known hashes
-    // We'll get this from the download_grid_hashes call, but for now return basic info
-    Ok(Some(DetectedGrid {
-        grid_type: "unknown".to_string(),
-        name: "Current Grid".to_string(),
-        date: "Unknown".to_string(),
-        hash,
-        is_known: false,
-    }))
-}
-
-#[tauri::command]
-fn match_grid_hash(
-    grid_hash: String,
-    grid_hashes: GridHashes,
-) -> Result<Option<DetectedGrid>, String> {
-    for (filename, hash) in &grid_hashes.hashes {
-        if hash == &grid_hash {
-            // Parse filename to extract grid type and date
-            // Format: dota2protracker_hero_grid_[type]_config_[date]_p[version]_[patch].json
-            let parts: Vec<&str> = filename.split('_').collect();
-            let grid_type = if filename.contains("d2pt_rating") {
-                "d2pt"
-            } else if filename.contains("high_winrate") {
-                "high_winrate"
-            } else if filename.contains("most_played") {
-                "most_played"
-            } else {
-                "unknown"
-            };
-
-            let date = parts
-                .iter()
-                .find(|p| p.starts_with("20"))
-                .map(|s| s.to_string())
-                .unwrap_or_else(|| "Unknown".to_string());
-
-            return Ok(Some(DetectedGrid {
-                grid_type: grid_type.to_string(),
-                name: filename.clone(),
-                date,
-                hash: hash.clone(),
-                is_known: true,
-            }));
+    if purge_backups {
+        for (_, cfg_dir) in list_profile_cfg_dirs().map_err(AppError::IoError)? {
+            let backups = list_backup_files(&cfg_dir);
+            for (path, _, _) in backups.iter().rev().skip(1) {
+                remove(path);
+            }
         }
     }
 
-    Ok(None)
+    info!(
+        "Cleared cache: {} file(s), {} bytes",
+        report.files_removed, report.bytes_removed
+    );
+    Ok(report)
 }
 
-/*
+/// Returns the system scale factor of the monitor the main window is on,
+/// falling back to a guess from the screen size and device pixel ratio when
+/// the monitor can't be determined. Capped to 1.0-2.5.
 #[tauri::command]
-async fn activate_grid(app: AppHandle, grid_name: String, download_url: String) -> Result<(), String> {
-    Ok(())
+fn estimate_system_dpi_scale(
+    app: tauri::AppHandle,
+    screen_width: f64,
+    screen_height: f64,
+    device_pixel_ratio: f64,
+) -> f64 {
+    let scale = monitor_scale_factor(&app).unwrap_or_else(|| {
+        info!("Monitor info unavailable, estimating DPI scale from the screen size");
+        heuristic_dpi_scale(screen_width, screen_height, device_pixel_ratio)
+    });
+    if scale > 2.5 {
+        2.5 // Cap at 250% to avoid extreme values
+    } else if scale < 0.8 {
+        1.0 // Minimum reasonable scale
+    } else {
+        scale
+    }
 }
 
-#[tauri::command]
-async fn clear_cache(app: AppHandle) -> Result<(), String> {
-    Ok(())
+/// Scale factor of the monitor the main window is currently on, falling back
+/// to the primary monitor.
+fn monitor_scale_factor(app: &tauri::AppHandle) -> Option<f64> {
+    let window = app.get_webview_window("main")?;
+    let monitor = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .or_else(|| window.primary_monitor().ok().flatten())?;
+    let scale = monitor.scale_factor();
+    debug!("Monitor {:?} scale factor: {}", monitor.name(), scale);
+    (scale > 0.0).then_some(scale)
 }
-*/
 
-#[tauri::command]
-fn estimate_system_dpi_scale(
+fn heuristic_dpi_scale(
     #[allow(unused_variables)] screen_width: f64,
     #[allow(unused_variables)] screen_height: f64,
     device_pixel_ratio: f64,
@@ -346,15 +6315,31 @@ fn estimate_system_dpi_scale(
         }
     }
 
-    // For other platforms or unknown configurations, trust devicePixelRatio
-    // but cap it to reasonable values
-    if device_pixel_ratio > 2.5 {
-        2.5 // Cap at 250% to avoid extreme values
-    } else if device_pixel_ratio < 0.8 {
-        1.0 // Minimum reasonable scale
-    } else {
-        device_pixel_ratio
-    }
+    // For other platforms or unknown configurations, trust devicePixelRatio;
+    // the caller caps it to reasonable values
+    device_pixel_ratio
+}
+
+/// Lets the UI check notification permission before relying on update
+/// notifications, which otherwise fail silently where permission is required.
+#[tauri::command]
+fn check_notification_permission(app: tauri::AppHandle) -> Result<PermissionState, AppError> {
+    app.notification()
+        .permission_state()
+        .map_err(|e| AppError::Other(format!("Failed to check notification permission: {}", e)))
+}
+
+#[tauri::command]
+fn request_notification_permission(app: tauri::AppHandle) -> Result<PermissionState, AppError> {
+    app.notification()
+        .request_permission()
+        .map_err(|e| AppError::Other(format!("Failed to request notification permission: {}", e)))
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WindowSettings {
+    pub minimize_to_tray: bool,
+    pub start_minimized: bool,
 }
 
 #[derive(Default)]
@@ -363,66 +6348,361 @@ pub struct AppSettings {
     pub start_minimized: AtomicBool,
 }
 
+// Auto-detected cfg directory, reused until the override changes
+#[derive(Default)]
+pub struct DetectionCache {
+    pub config_path: Mutex<Option<PathBuf>>,
+}
+
+// Bumped whenever scheduled backups are restarted or stopped
+#[derive(Default)]
+pub struct BackupScheduler {
+    pub generation: AtomicU64,
+}
+
+// Bumped whenever the update check interval changes
+#[derive(Default)]
+pub struct UpdateScheduler {
+    pub generation: AtomicU64,
+}
+
+#[tauri::command]
+fn get_config_path_override(app: tauri::AppHandle) -> Option<String> {
+    read_setting(&app, "config_path_override")
+}
+
+/// Sets (or clears, with `None`) the cfg directory override. The cached
+/// detection result is dropped and "config-path-changed" is emitted so the
+/// frontend re-runs detection without a restart.
+#[tauri::command]
+fn set_config_path_override(app: tauri::AppHandle, path: Option<String>) -> Result<(), AppError> {
+    if let Some(path) = &path {
+        if !Path::new(path).is_dir() {
+            return Err(AppError::ConfigNotFound(format!(
+                "{} is not a directory",
+                path
+            )));
+        }
+    }
+    info!("Setting config_path_override to: {:?}", path);
+    write_setting(&app, "config_path_override", &path).map_err(AppError::IoError)?;
+
+    *app.state::<DetectionCache>().config_path.lock().unwrap() = None;
+    app.emit("config-path-changed", &path)
+        .map_err(|e| AppError::Other(format!("Failed to emit config-path-changed: {}", e)))
+}
+
+/// Window settings as persisted in the settings store, with their defaults
+/// for keys that were never written.
+fn load_window_settings(app: &tauri::AppHandle) -> WindowSettings {
+    WindowSettings {
+        minimize_to_tray: read_setting(app, "minimize_to_tray").unwrap_or(true),
+        start_minimized: read_setting(app, "start_minimized").unwrap_or(false),
+    }
+}
+
+fn save_window_settings(app: &tauri::AppHandle, window: &WindowSettings) -> Result<(), String> {
+    write_setting(app, "minimize_to_tray", window.minimize_to_tray)?;
+    write_setting(app, "start_minimized", window.start_minimized)
+}
+
 #[tauri::command]
-fn set_minimize_to_tray(_app: tauri::AppHandle, enabled: bool, settings: State<AppSettings>) {
-    println!("set_minimize_to_tray called");
-    println!("Setting minimize_to_tray to: {}", enabled);
+fn set_minimize_to_tray(app: tauri::AppHandle, enabled: bool, settings: State<AppSettings>) {
+    info!("Setting minimize_to_tray to: {}", enabled);
     settings
         .minimize_to_tray
         .store(enabled, AtomicOrdering::SeqCst);
 
-    // minimize_to_tray persistence is handled through localStorage -> initialize_settings flow
+    if let Err(e) = write_setting(&app, "minimize_to_tray", enabled) {
+        error!("Failed to save minimize_to_tray: {}", e);
+    }
+}
+
+/// What closing the main window does under the current settings: "hide"
+/// when minimize_to_tray is on, "quit" otherwise. There is no "ask" setting
+/// yet, so it is never returned.
+fn resolve_close_behavior(app: &tauri::AppHandle) -> &'static str {
+    if app
+        .state::<AppSettings>()
+        .minimize_to_tray
+        .load(AtomicOrdering::SeqCst)
+    {
+        "hide"
+    } else {
+        "quit"
+    }
+}
+
+/// Reports what the window would do on close without closing it, so the
+/// tray settings can be checked from the UI.
+#[tauri::command]
+fn simulate_close_behavior(app: tauri::AppHandle) -> String {
+    resolve_close_behavior(&app).to_string()
+}
+
+// Passed to the app when the OS starts it at login
+const AUTOSTART_ARG: &str = "--autostart";
+
+#[tauri::command]
+fn get_launch_at_login(app: tauri::AppHandle) -> bool {
+    read_setting(&app, "launch_at_login").unwrap_or(false)
+}
+
+/// Registers the app to start at login (the Run registry key on Windows, a
+/// LaunchAgent on macOS, an XDG autostart entry on Linux) or removes that
+/// registration. Login launches start hidden in the tray. Enabling rewrites
+/// the same entry, so toggling repeatedly never leaves duplicates.
+#[tauri::command]
+fn set_launch_at_login(app: tauri::AppHandle, enabled: bool) -> Result<(), AppError> {
+    info!("Setting launch_at_login to: {}", enabled);
+    let autolaunch = app.autolaunch();
+    if enabled {
+        autolaunch.enable().map_err(|e| {
+            AppError::PermissionDenied(format!("Failed to register launch at login: {}", e))
+        })?;
+    } else if autolaunch.is_enabled().unwrap_or(true) {
+        autolaunch.disable().map_err(|e| {
+            AppError::PermissionDenied(format!("Failed to remove launch at login: {}", e))
+        })?;
+    }
+    write_setting(&app, "launch_at_login", enabled).map_err(AppError::IoError)
+}
+
+// Written to app_data_dir()/logs as <name>.log
+const LOG_FILE_NAME: &str = "d2pt-grid-updater";
+
+fn log_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("logs"))
+        .map_err(|e| format!("Could not get app data directory: {}", e))
+}
+
+/// Path of the log file, for attaching to bug reports.
+#[tauri::command]
+fn get_log_path(app: tauri::AppHandle) -> Result<String, AppError> {
+    let path = log_dir(&app)
+        .map_err(AppError::IoError)?
+        .join(format!("{}.log", LOG_FILE_NAME));
+    Ok(path.to_string_lossy().to_string())
 }
 
 #[tauri::command]
 fn set_start_minimized(app: tauri::AppHandle, enabled: bool, settings: State<AppSettings>) {
-    println!("Setting start_minimized to: {}", enabled);
+    info!("Setting start_minimized to: {}", enabled);
     settings
         .start_minimized
         .store(enabled, AtomicOrdering::SeqCst);
 
-    // Also save to persistent file
-    println!("Saving start_minimized={} to file", enabled);
-    if let Ok(app_data_dir) = app.path().app_data_dir() {
-        let file_path = app_data_dir.join("start_minimized.txt");
-        match fs::write(&file_path, if enabled { "true" } else { "false" }) {
-            Ok(_) => println!(
-                "Successfully saved start_minimized to file: {:?}",
-                file_path
-            ),
-            Err(e) => println!("Failed to save start_minimized to file: {}", e),
-        }
-    } else {
-        println!("Could not get app data directory");
+    if let Err(e) = write_setting(&app, "start_minimized", enabled) {
+        error!("Failed to save start_minimized: {}", e);
     }
 }
 
+/// Returns the window settings from the store, which is the source of
+/// truth. The values passed in come from the frontend's localStorage, where
+/// older versions kept them; they're only imported for keys the store
+/// doesn't have yet.
 #[tauri::command]
 fn initialize_settings(
     app: tauri::AppHandle,
     minimize_to_tray: bool,
     start_minimized: bool,
     settings: State<AppSettings>,
-) {
-    println!(
+) -> WindowSettings {
+    let window = WindowSettings {
+        minimize_to_tray: read_setting(&app, "minimize_to_tray").unwrap_or(minimize_to_tray),
+        start_minimized: read_setting(&app, "start_minimized").unwrap_or(start_minimized),
+    };
+    info!(
         "Initializing settings: minimize_to_tray={}, start_minimized={}",
-        minimize_to_tray, start_minimized
+        window.minimize_to_tray, window.start_minimized
     );
     settings
         .minimize_to_tray
-        .store(minimize_to_tray, AtomicOrdering::SeqCst);
+        .store(window.minimize_to_tray, AtomicOrdering::SeqCst);
     settings
         .start_minimized
-        .store(start_minimized, AtomicOrdering::SeqCst);
+        .store(window.start_minimized, AtomicOrdering::SeqCst);
+
+    if let Err(e) = save_window_settings(&app, &window) {
+        error!("Failed to save window settings: {}", e);
+    }
+    window
+}
+
+/// The value stored in a legacy start_minimized.txt; anything but "true"
+/// (e.g. an empty or garbled file) means false, as older versions read it.
+fn parse_legacy_start_minimized(content: &str) -> bool {
+    content.trim() == "true"
+}
+
+/// Imports the start_minimized.txt file written by older versions into the
+/// settings store and removes it. Returns whether a legacy file was found.
+/// The stored value wins if both exist.
+#[tauri::command]
+fn migrate_legacy_settings(app: tauri::AppHandle) -> Result<bool, AppError> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::IoError(format!("Could not get app data directory: {}", e)))?;
+    let legacy_path = app_data_dir.join("start_minimized.txt");
+    let content = match fs::read_to_string(&legacy_path) {
+        Ok(content) => content,
+        Err(_) => return Ok(false),
+    };
+
+    if read_setting::<bool>(&app, "start_minimized").is_none() {
+        let start_minimized = parse_legacy_start_minimized(&content);
+        info!(
+            "Migrating start_minimized={} from {:?}",
+            start_minimized, legacy_path
+        );
+        write_setting(&app, "start_minimized", start_minimized).map_err(AppError::IoError)?;
+    }
+    fs::remove_file(&legacy_path)
+        .map_err(|e| AppError::IoError(format!("Failed to remove {:?}: {}", legacy_path, e)))?;
+    Ok(true)
+}
+
+// What the tray's status line says about the installed grid
+#[derive(Default)]
+pub enum TrayStatus {
+    #[default]
+    Detecting,
+    NoConfig,
+    Current(String),
+}
+
+// Grids currently listed in the tray's "Activate Grid" submenu, and the
+// status line shown above it
+#[derive(Default)]
+pub struct TrayGrids {
+    pub grids: Mutex<Vec<Grid>>,
+    pub status: Mutex<TrayStatus>,
+}
+
+fn build_tray_menu(
+    app: &tauri::AppHandle,
+    grids: &[Grid],
+    status: &TrayStatus,
+) -> tauri::Result<Menu<tauri::Wry>> {
+    let show_item = MenuItem::with_id(app, "show", "Show", true, None::<&str>)?;
+    let status_label = match status {
+        TrayStatus::Detecting => "Detecting current grid...",
+        TrayStatus::NoConfig => "No Dota config detected",
+        TrayStatus::Current(label) => label.as_str(),
+    };
+    let status_item = MenuItem::with_id(app, "status", status_label, false, None::<&str>)?;
+    let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+    // Without a cfg directory there is nothing to switch
+    if matches!(status, TrayStatus::NoConfig) {
+        return Menu::with_items(app, &[&status_item, &show_item, &quit_item]);
+    }
+
+    let update_item = MenuItem::with_id(
+        app,
+        "update_latest",
+        "Update to Latest Grid",
+        true,
+        None::<&str>,
+    )?;
+    let grids_menu = Submenu::with_id(app, "grids", "Activate Grid", !grids.is_empty())?;
+    for grid in grids {
+        let label = format!("Latest {} ({})", grid.grid_type, grid.date);
+        let item = MenuItem::with_id(
+            app,
+            format!("grid:{}", grid.name),
+            label,
+            true,
+            None::<&str>,
+        )?;
+        grids_menu.append(&item)?;
+    }
+    Menu::with_items(
+        app,
+        &[
+            &status_item,
+            &show_item,
+            &update_item,
+            &grids_menu,
+            &quit_item,
+        ],
+    )
+}
 
-    // Persistence is handled by frontend localStorage for minimize_to_tray
-    // Save start_minimized to file for persistence across restarts
-    if let Ok(app_data_dir) = app.path().app_data_dir() {
-        let file_path = app_data_dir.join("start_minimized.txt");
-        let _ = fs::write(&file_path, if start_minimized { "true" } else { "false" });
+/// Rebuilds the tray menu from the grids and status in TrayGrids.
+fn rebuild_tray_menu(app: &tauri::AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let state = app.state::<TrayGrids>();
+    let grids = state.grids.lock().unwrap().clone();
+    let status = state.status.lock().unwrap();
+    let result = build_tray_menu(app, &grids, &status).and_then(|menu| tray.set_menu(Some(menu)));
+    if let Err(e) = result {
+        warn!("Failed to rebuild tray menu: {}", e);
     }
 }
 
+/// Lists the newest grid of each type in the tray.
+fn refresh_tray_grids(app: &tauri::AppHandle, grids: &[Grid]) {
+    let latest = ["d2pt", "high_winrate", "most_played"]
+        .iter()
+        .filter_map(|grid_type| newest_grid_of_type(grids, grid_type))
+        .cloned()
+        .collect();
+    *app.state::<TrayGrids>().grids.lock().unwrap() = latest;
+    rebuild_tray_menu(app);
+}
+
+/// Detects the installed grid in the background and shows it in the tray's
+/// status line.
+fn refresh_tray_status(app: &tauri::AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let status = match resolve_config_dir(&app, None) {
+            Ok(config_dir) => {
+                let config_path = Some(config_dir.to_string_lossy().to_string());
+                match detect_current_grid(app.clone(), config_path, None).await {
+                    Ok(Some(grid)) if grid.is_known => {
+                        TrayStatus::Current(format!("Current: {} ({})", grid.grid_type, grid.date))
+                    }
+                    Ok(Some(grid)) => {
+                        TrayStatus::Current(format!("Current: {} grid", grid.grid_type))
+                    }
+                    Ok(None) => TrayStatus::Current("No grid installed".to_string()),
+                    Err(e) => {
+                        warn!("Failed to detect grid for the tray: {}", e);
+                        TrayStatus::Current("Current grid unknown".to_string())
+                    }
+                }
+            }
+            Err(e) => {
+                info!("No Dota config for the tray: {}", e);
+                TrayStatus::NoConfig
+            }
+        };
+        *app.state::<TrayGrids>().status.lock().unwrap() = status;
+        rebuild_tray_menu(&app);
+    });
+}
+
+async fn activate_tray_grid(app: &tauri::AppHandle, name: &str) -> Result<Grid, String> {
+    let grid = app
+        .state::<TrayGrids>()
+        .grids
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|grid| grid.name == name)
+        .cloned()
+        .ok_or_else(|| format!("{} is no longer listed", name))?;
+    let config_dir = resolve_config_dir(app, None)?;
+    install_grid_with_retry(app, &config_dir, &grid.download_url).await?;
+    Ok(grid)
+}
+
 fn handle_tray_event(tray_icon: &tauri::tray::TrayIcon, event: TrayIconEvent) {
     match event {
         TrayIconEvent::DoubleClick { .. } => {
@@ -443,10 +6723,35 @@ fn handle_menu_event(app: &tauri::AppHandle, event: MenuEvent) {
             window.show().unwrap();
             window.set_focus().unwrap();
         }
+        "update_latest" => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                match update_to_latest(&app).await {
+                    Ok(grid) => info!("Updated to latest grid from tray: {}", grid.name),
+                    Err(e) => error!("Failed to update to latest grid from tray: {}", e),
+                }
+            });
+        }
         "quit" => {
             app.exit(0);
         }
-        _ => {}
+        id => {
+            let Some(name) = id.strip_prefix("grid:") else {
+                return;
+            };
+            let app = app.clone();
+            let name = name.to_string();
+            tauri::async_runtime::spawn(async move {
+                let (title, body) = match activate_tray_grid(&app, &name).await {
+                    Ok(grid) => ("Grid activated", grid.name),
+                    Err(e) => ("Failed to activate grid", e),
+                };
+                info!("{} from tray: {}", title, body);
+                if let Err(e) = app.notification().builder().title(title).body(body).show() {
+                    warn!("Failed to show notification: {}", e);
+                }
+            });
+        }
     }
 }
 
@@ -455,16 +6760,50 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_store::Builder::default().build())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_autostart::init(
+            MacosLauncher::LaunchAgent,
+            Some(vec![AUTOSTART_ARG]),
+        ))
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(|app, _shortcut, event| {
+                    if event.state() == ShortcutState::Pressed {
+                        handle_grid_hotkey(app);
+                    }
+                })
+                .build(),
+        )
         .manage(AppSettings {
             minimize_to_tray: AtomicBool::new(true),
             start_minimized: AtomicBool::new(false),
         })
+        .manage(DetectionCache::default())
+        .manage(BackupScheduler::default())
+        .manage(UpdateScheduler::default())
+        .manage(TrayGrids::default())
+        .manage(HttpClient {
+            client: Mutex::new(reqwest::Client::new()),
+        })
         .setup(|app| {
-            let show_item = MenuItem::with_id(app, "show", "Show", true, None::<&str>)?;
-            let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-            let menu = Menu::with_items(app, &[&show_item, &quit_item])?;
+            // Registered here rather than on the builder because the log
+            // directory lives under the app data directory
+            app.handle().plugin(
+                tauri_plugin_log::Builder::new()
+                    .level(log::LevelFilter::Info)
+                    .targets([
+                        tauri_plugin_log::Target::new(tauri_plugin_log::TargetKind::Stdout),
+                        tauri_plugin_log::Target::new(tauri_plugin_log::TargetKind::Folder {
+                            path: log_dir(app.handle())?,
+                            file_name: Some(LOG_FILE_NAME.to_string()),
+                        }),
+                    ])
+                    .build(),
+            )?;
+
+            let menu = build_tray_menu(app.handle(), &[], &TrayStatus::Detecting)?;
 
-            let _tray = TrayIconBuilder::new()
+            let _tray = TrayIconBuilder::with_id(TRAY_ID)
                 .icon(app.default_window_icon().unwrap().clone())
                 .menu(&menu)
                 .show_menu_on_left_click(false)
@@ -472,62 +6811,112 @@ pub fn run() {
                 .on_menu_event(handle_menu_event)
                 .build(app)?;
 
-            // Check start_minimized setting from file and set initial window visibility
+            if let Err(e) = migrate_legacy_settings(app.handle().clone()) {
+                error!("Failed to migrate legacy settings: {}", e);
+            }
+            if let Err(e) = migrate_github_token(app.handle()) {
+                warn!("Failed to move the GitHub token out of settings: {}", e);
+            }
+            reconcile_pending_writes();
+
+            // A bad saved proxy falls back to the environment's settings but
+            // is reported, since downloads will likely fail behind the proxy
+            let proxy_url = get_proxy_url(app.handle().clone());
+            match build_http_client(proxy_url.as_deref()) {
+                Ok(client) => *app.state::<HttpClient>().client.lock().unwrap() = client,
+                Err(e) => {
+                    warn!("{}", e);
+                    let _ = app
+                        .notification()
+                        .builder()
+                        .title("Proxy setting ignored")
+                        .body(e)
+                        .show();
+                }
+            }
+
+            // Load persisted window settings so closing behaves right even
+            // before the frontend initializes
+            let window_settings = load_window_settings(app.handle());
+            let settings = app.state::<AppSettings>();
+            settings
+                .minimize_to_tray
+                .store(window_settings.minimize_to_tray, AtomicOrdering::SeqCst);
+            settings
+                .start_minimized
+                .store(window_settings.start_minimized, AtomicOrdering::SeqCst);
+
+            // Fill the tray's grid submenu from the last known list, then
+            // from GitHub, and show which grid is installed
+            refresh_tray_status(app.handle());
+            if let Some(cache) = read_grid_list_cache(app.handle()) {
+                refresh_tray_grids(app.handle(), &cache.grids);
+            }
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = load_remote_grid_list(&handle, false).await {
+                    warn!("Failed to load grids for the tray: {}", e);
+                }
+            });
+
+            let backup_interval: u64 =
+                read_setting(app.handle(), "auto_backup_interval_hours").unwrap_or(0);
+            if backup_interval > 0 {
+                spawn_auto_backup(app.handle().clone(), backup_interval);
+            }
+            let update_interval = get_update_check_interval_hours(app.handle().clone());
+            if update_interval > 0 {
+                spawn_update_checker(app.handle().clone(), update_interval);
+            }
+            register_grid_hotkey(app.handle());
+            // Re-registering keeps the login item pointing at this executable
+            // if the app was moved or updated
+            if get_launch_at_login(app.handle().clone()) {
+                if let Err(e) = app.autolaunch().enable() {
+                    warn!("Failed to refresh launch at login: {}", e);
+                }
+            }
+
+            // Check start_minimized setting and set initial window visibility.
+            // Launches at login always stay in the tray.
             let window = app.get_webview_window("main").unwrap();
-            println!("Checking file for start_minimized setting...");
-            if let Ok(app_data_dir) = app.path().app_data_dir() {
-                let file_path = app_data_dir.join("start_minimized.txt");
-                println!("Looking for start_minimized file: {:?}", file_path);
-                match fs::read_to_string(&file_path) {
-                    Ok(content) => {
-                        println!("Read start_minimized file content: '{}'", content.trim());
-                        let start_minimized = content.trim() == "true";
-                        println!("Parsed start_minimized = {}", start_minimized);
-                        if !start_minimized {
-                            println!("Showing window based on file setting (start_minimized=false)");
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                        } else {
-                            println!("Keeping window hidden based on file setting (start_minimized=true)");
-                            // Window is already hidden by default, so do nothing
-                        }
-                    }
-                    Err(e) => {
-                        println!(
-                            "Could not read start_minimized file ({}), defaulting to show window",
-                            e
-                        );
-                        let _ = window.show();
-                        let _ = window.set_focus();
-                    }
+            let launched_at_login = std::env::args().any(|arg| arg == AUTOSTART_ARG);
+            match read_setting::<bool>(app.handle(), "start_minimized") {
+                _ if launched_at_login => {
+                    info!("Keeping window hidden, launched at login");
+                }
+                Some(true) => {
+                    info!("Keeping window hidden based on stored setting (start_minimized=true)");
+                    // Window is already hidden by default, so do nothing
+                }
+                Some(false) => {
+                    info!("Showing window based on stored setting (start_minimized=false)");
+                    let _ = window.show();
+                    let _ = window.set_focus();
+                }
+                None => {
+                    info!("No start_minimized setting stored, defaulting to show window");
+                    let _ = window.show();
+                    let _ = window.set_focus();
                 }
-            } else {
-                println!("Could not get app data directory, defaulting to show window");
-                let _ = window.show();
-                let _ = window.set_focus();
             }
 
             Ok(())
         })
         .on_window_event(|window, event| match event {
             tauri::WindowEvent::CloseRequested { api, .. } => {
-                let app = window.app_handle();
-                let minimize_to_tray = app
-                    .state::<AppSettings>()
-                    .minimize_to_tray
-                    .load(AtomicOrdering::SeqCst);
-
-                println!(
-                    "Window close requested, minimize_to_tray = {}",
-                    minimize_to_tray
-                );
+                let behavior = resolve_close_behavior(window.app_handle());
 
-                if minimize_to_tray {
-                    println!("Minimizing to tray");
-                    window.hide().unwrap();
+                info!("Window close requested, behavior = {}", behavior);
+
+                if behavior == "hide" {
+                    info!("Minimizing to tray");
+                    if let Err(e) = window.hide() {
+                        warn!("Failed to hide window: {}", e);
+                    }
                     api.prevent_close();
                 } else {
-                    println!("Allowing app to close");
+                    info!("Allowing app to close");
                     // If minimize_to_tray is false, allow the app to close normally
                 }
             }
@@ -535,15 +6924,397 @@ pub fn run() {
         })
         .invoke_handler(tauri::generate_handler![
             find_dota_config_path,
+            get_dota_config_path,
+            list_dota_cfg_dirs,
+            list_dota_config_paths,
+            get_extra_dota_app_ids,
+            set_extra_dota_app_ids,
             list_remote_grids,
+            list_remote_grids_grouped,
             estimate_system_dpi_scale,
             download_grid_hashes,
             detect_current_grid,
             match_grid_hash,
+            diff_remote_grid_lists,
+            suggest_best_grid,
+            list_grids_for_patch,
+            list_grids_in_range,
+            get_detection_status,
+            get_profile_count,
+            compare_profiles,
+            find_duplicate_profiles,
+            benchmark_detection,
+            describe_grid,
+            check_for_updates,
+            get_auto_update_status,
+            get_auto_apply_grid_type,
+            set_auto_apply_grid_type,
+            get_update_check_interval_hours,
+            set_update_check_interval_hours,
+            get_grid_hotkey,
+            set_grid_hotkey,
+            get_battery_check_policy,
+            set_battery_check_policy,
+            current_grid_age_days,
+            check_clock_skew,
+            activate_if_unmodified,
+            get_remembered_grid_type,
+            is_app_managed_grid,
+            clear_app_managed_marker,
+            get_mark_managed_grids,
+            set_mark_managed_grids,
+            export_history_csv,
+            get_dota_language,
+            activate_grid,
+            is_dota_running,
+            clear_cache,
+            activate_grid_smart,
+            apply_grid_to_path,
+            merge_preview,
+            activate_grid_as,
+            remove_grid,
+            category_changes,
+            category_count_diff,
+            diff_grid,
+            normalize_grid,
+            get_pinned_grid,
+            pin_grid,
+            unpin_grid,
+            reapply_pinned_grid,
+            is_default_grid,
+            validate_grid_file,
+            detect_name_collisions,
+            fix_name_collisions,
+            check_config_count,
+            validate_hero_ids,
+            find_stale_hero_references,
+            get_rate_limit_status,
+            snapshot_all_users,
+            list_snapshot_sets,
+            restore_all_users,
+            get_max_backups,
+            set_max_backups,
+            get_max_backups_size_mb,
+            set_max_backups_size_mb,
+            prune_grid_backups,
+            list_grid_backups,
+            restore_grid_backup,
+            preview_undo,
+            undo_last_activation,
+            start_auto_backup,
+            stop_auto_backup,
+            get_auto_backup_status,
+            create_backup_session,
+            list_backup_sessions,
+            restore_session,
+            detect_unknown_grid_fields,
+            verify_write_roundtrip,
+            check_write_strategy,
+            repair_config_permissions,
+            can_write_config,
+            get_debug_mode,
+            set_debug_mode,
+            fetch_raw_contents,
+            fetch_source_info,
+            get_rank,
+            set_rank,
+            recommend_grid_for_rank,
+            get_mirror_urls,
+            set_mirror_urls,
+            has_github_token,
+            set_github_token,
+            get_allowed_source_urls,
+            set_allowed_source_urls,
+            get_bandwidth_limit_kbps,
+            set_bandwidth_limit_kbps,
+            get_cloud_retry_count,
+            get_network_retry_attempts,
+            get_proxy_url,
+            get_log_path,
+            get_launch_at_login,
+            set_launch_at_login,
+            get_grid_source,
+            set_grid_source,
+            set_proxy_url,
+            set_network_retry_attempts,
+            set_cloud_retry_count,
+            get_write_check_window_ms,
+            set_write_check_window_ms,
+            check_notification_permission,
+            request_notification_permission,
+            get_config_path_override,
+            set_config_path_override,
             set_minimize_to_tray,
+            simulate_close_behavior,
             set_start_minimized,
-            initialize_settings
+            initialize_settings,
+            migrate_legacy_settings
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v3_grid() -> serde_json::Value {
+        serde_json::json!({
+            "version": 3,
+            "configs": [{
+                "config_name": "Test",
+                "categories": [{
+                    "category_name": "Carry",
+                    "x_position": 0.0,
+                    "y_position": 0.0,
+                    "width": 100.0,
+                    "height": 50.0,
+                    "hero_ids": [1, 2]
+                }]
+            }]
+        })
+    }
+
+    #[test]
+    fn validates_version_3_grid() {
+        let validation = validate_grid_value(&v3_grid());
+        assert!(validation.valid, "{:?}", validation.errors);
+        assert_eq!(validation.version, Some(3));
+        assert_eq!(validation.config_count, 1);
+    }
+
+    #[test]
+    fn reports_missing_fields_for_version_3() {
+        let mut grid = v3_grid();
+        grid["configs"][0]["categories"][0]
+            .as_object_mut()
+            .unwrap()
+            .remove("hero_ids");
+        let validation = validate_grid_value(&grid);
+        assert!(!validation.valid);
+        assert_eq!(
+            validation.errors,
+            vec!["configs[0].categories[0] is missing \"hero_ids\"".to_string()]
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_version() {
+        let mut grid = v3_grid();
+        grid["version"] = serde_json::json!(4);
+        let validation = validate_grid_value(&grid);
+        assert!(!validation.valid);
+        assert_eq!(validation.version, Some(4));
+        assert!(validation.errors[0].starts_with("Unsupported grid version 4"));
+    }
+
+    #[test]
+    fn rejects_missing_version() {
+        let mut grid = v3_grid();
+        grid.as_object_mut().unwrap().remove("version");
+        let validation = validate_grid_value(&grid);
+        assert!(!validation.valid);
+        assert!(validation
+            .errors
+            .contains(&"Missing \"version\" field".to_string()));
+    }
+
+    #[test]
+    fn parse_grid_config_explains_failures() {
+        let content = serde_json::to_vec(&v3_grid()).unwrap();
+        assert_eq!(parse_grid_config(&content).unwrap().configs.len(), 1);
+
+        let empty = parse_grid_config(b"  ").unwrap_err();
+        assert!(empty.contains("empty"), "{}", empty);
+        let html = parse_grid_config(b"<html></html>").unwrap_err();
+        assert!(html.contains("HTML"), "{}", html);
+        let truncated = parse_grid_config(&content[..content.len() / 2]).unwrap_err();
+        assert!(truncated.contains("truncated"), "{}", truncated);
+        let wrong_shape = parse_grid_config(b"{\"configs\": 1}").unwrap_err();
+        assert!(wrong_shape.contains("grid format"), "{}", wrong_shape);
+    }
+
+    #[test]
+    fn parses_dates_and_patches_from_filenames() {
+        let name = "dota2protracker_hero_grid_d2pt_rating_config_2025-03-14_p7_41c.json";
+        assert_eq!(parse_grid_date(name), "2025-03-14");
+        assert_eq!(parse_grid_patch(name), Some("7.41c".to_string()));
+        assert_eq!(parse_grid_date("grid_20250314.json"), "2025-03-14");
+        assert_eq!(parse_grid_date("grid_p7_41c.json"), "Unknown");
+        assert_eq!(parse_grid_patch("grid_2025-03-14.json"), None);
+    }
+
+    #[test]
+    fn normalizes_patch_spellings() {
+        assert_eq!(normalize_patch("7.41c"), "741c");
+        assert_eq!(normalize_patch("741C"), "741c");
+        assert_eq!(normalize_patch(" p7_41c "), "741c");
+    }
+
+    fn grid(name: &str) -> Grid {
+        Grid {
+            name: name.to_string(),
+            date: parse_grid_date(name),
+            parsed_date: parse_grid_naive_date(name),
+            patch: parse_grid_patch(name),
+            grid_type: classify_grid_type(name).to_string(),
+            download_url: String::new(),
+        }
+    }
+
+    #[test]
+    fn picks_newest_grid_of_type() {
+        let grids = vec![
+            grid("d2pt_rating_config_2025-03-01.json"),
+            grid("high_winrate_config_2025-04-01.json"),
+            grid("d2pt_rating_config_2025-03-14.json"),
+            grid("d2pt_rating_config_2025-02-01.json"),
+        ];
+        let newest = newest_grid_of_type(&grids, "d2pt").unwrap();
+        assert_eq!(newest.name, "d2pt_rating_config_2025-03-14.json");
+        assert!(newest_grid_of_type(&grids, "most_played").is_none());
+    }
+
+    /// A fresh directory under the system temp dir, unique to the test.
+    fn test_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("d2pt-grid-updater-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn finds_mixed_case_dota_directory() {
+        let steam = test_dir("mixed-case");
+        let dota = steam.join("SteamApps").join("common").join("Dota 2 Beta");
+        fs::create_dir_all(&dota).unwrap();
+
+        let found = find_dir_case_insensitive(&steam, &["steamapps", "common", "dota 2 beta"]);
+        assert_eq!(found, Some(dota));
+        assert_eq!(find_dir_case_insensitive(&steam, &["userdata"]), None);
+        fs::remove_dir_all(&steam).unwrap();
+    }
+
+    #[test]
+    fn prefers_exact_case_match() {
+        let steam = test_dir("exact-case");
+        let exact = steam.join("userdata");
+        fs::create_dir_all(&exact).unwrap();
+        fs::create_dir_all(steam.join("UserData")).unwrap();
+
+        assert_eq!(
+            find_dir_case_insensitive(&steam, &["userdata"]),
+            Some(exact)
+        );
+        fs::remove_dir_all(&steam).unwrap();
+    }
+
+    #[test]
+    fn heuristic_dpi_scale_trusts_device_pixel_ratio() {
+        assert_eq!(heuristic_dpi_scale(1366.0, 768.0, 1.0), 1.0);
+        assert_eq!(heuristic_dpi_scale(1280.0, 720.0, 1.75), 1.75);
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn heuristic_dpi_scale_snaps_common_windows_scales() {
+        assert_eq!(heuristic_dpi_scale(1920.0, 1080.0, 1.3), 1.25);
+        assert_eq!(heuristic_dpi_scale(2560.0, 1440.0, 1.45), 1.5);
+    }
+
+    #[test]
+    fn parses_legacy_start_minimized_file() {
+        assert!(parse_legacy_start_minimized("true"));
+        assert!(parse_legacy_start_minimized("true\r\n"));
+        assert!(!parse_legacy_start_minimized("false\n"));
+        assert!(!parse_legacy_start_minimized(""));
+        assert!(!parse_legacy_start_minimized("TRUE"));
+    }
+
+    fn grid_file_hashes(name: &str, content: &[u8]) -> GridFileHashes {
+        let dir = test_dir(name);
+        let path = dir.join(GRID_FILE_NAME);
+        fs::write(&path, content).unwrap();
+        let hashes = hash_grid_file(&path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        hashes
+    }
+
+    #[test]
+    fn normalized_hash_ignores_trailing_newline() {
+        let published = b"{\"version\": 3,\n\"configs\": []}";
+        let expected = format!("{:x}", md5::compute(published));
+
+        let plain = grid_file_hashes("no-newline", published);
+        assert_eq!(plain.raw, expected);
+        assert_eq!(plain.normalized, expected);
+
+        let with_newline = grid_file_hashes("newline", &[&published[..], b"\n"].concat());
+        assert_ne!(with_newline.raw, expected);
+        assert_eq!(with_newline.normalized, expected);
+    }
+
+    #[test]
+    fn normalized_hash_ignores_bom_and_crlf() {
+        let published = b"{\"version\": 3,\n\"configs\": []}";
+        let expected = format!("{:x}", md5::compute(published));
+        let edited = b"\xEF\xBB\xBF{\"version\": 3,\r\n\"configs\": []}\r\n \t";
+
+        let hashes = grid_file_hashes("bom-crlf", edited);
+        assert_eq!(hashes.raw, format!("{:x}", md5::compute(edited)));
+        assert_eq!(hashes.normalized, expected);
+    }
+
+    #[test]
+    fn streaming_hash_matches_full_read() {
+        let dir = test_dir("streaming-hash");
+        let path = dir.join(GRID_FILE_NAME);
+        // Spans several read chunks and ends mid-chunk
+        let content: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+        fs::write(&path, content).unwrap();
+
+        let expected = format!("{:x}", md5::compute(fs::read(&path).unwrap()));
+        assert_eq!(hash_file(&path).unwrap(), expected);
+        assert_eq!(hash_grid_file(&path).unwrap().raw, expected);
+
+        fs::write(&path, b"").unwrap();
+        assert_eq!(
+            hash_file(&path).unwrap(),
+            format!("{:x}", md5::compute(b""))
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn atomic_write_replaces_stale_temp_file() {
+        let dir = test_dir("atomic-stale");
+        let path = dir.join(GRID_FILE_NAME);
+        let tmp_path = dir.join(format!("{}.tmp", GRID_FILE_NAME));
+        fs::write(&path, b"old").unwrap();
+        // Left behind by an interrupted write
+        fs::write(&tmp_path, b"partial garbage").unwrap();
+
+        assert_eq!(
+            write_file_atomic_with_strategy(&path, b"new").unwrap(),
+            "rename"
+        );
+        assert_eq!(fs::read(&path).unwrap(), b"new");
+        assert!(!tmp_path.exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn failed_atomic_write_cleans_up_temp_file() {
+        let dir = test_dir("atomic-failed");
+        // A directory in the target's place makes the rename fail
+        let path = dir.join(GRID_FILE_NAME);
+        fs::create_dir_all(path.join("child")).unwrap();
+
+        assert!(write_file_atomic(&path, b"new").is_err());
+        assert!(path.is_dir());
+        assert!(!dir.join(format!("{}.tmp", GRID_FILE_NAME)).exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -24,6 +24,7 @@ interface Grid {
   key: string;
   name: string;
   date: string;
+  parsed_date: string | null; // ISO date, null when the name has none
   download_url: string;
 }
 
@@ -40,14 +41,42 @@ interface DetectedGrid {
   grid_type: string;
   name: string;
   date: string;
+  parsed_date: string | null;
   hash: string;
   is_known: boolean;
 }
 
+interface GridActivation {
+  hash: string;
+  verified: boolean;
+  warning: string | null;
+  dry_run: {
+    target_path: string;
+    would_overwrite: boolean;
+    validation: { valid: boolean; errors: string[] };
+  } | null;
+}
+
+interface RemoteGridList {
+  grids: Grid[];
+  fetched_at: number;
+  stale: boolean;
+}
+
 interface GridHashes {
   hashes: { [filename: string]: string };
 }
 
+// Commands returning AppError reject with { code, message }; the others
+// still reject with a plain string
+interface AppError {
+  code: string;
+  message: string;
+}
+
+const errorMessage = (e: any): string =>
+  typeof e === "string" ? e : (e?.message ?? String(e));
+
 function App() {
   const [dotaPath, setDotaPath] = useState("Searching for Dota 2 path...");
   const [grids, setGrids] = useState<GroupedGrid[]>([]);
@@ -80,9 +109,12 @@ function App() {
 
     setApplyingGrid(grid.download_url);
     try {
-      await invoke("activate_grid", {
+      const result: GridActivation = await invoke("activate_grid", {
         gridName: grid.name,
         downloadUrl: grid.download_url,
+        force: false,
+        merge: false,
+        dryRun: false,
       });
 
       notification.success({
@@ -90,26 +122,33 @@ function App() {
         description: `Successfully applied ${grid.name}`,
         placement: "bottomRight",
       });
+      if (result.warning) {
+        notification.warning({
+          title: "Grid Not Verified",
+          description: result.warning,
+          placement: "bottomRight",
+        });
+      }
 
       // Refresh the detected grid after applying
-      const path: string | null = await invoke("find_dota_config_path");
+      const path: string | null = await invoke("get_dota_config_path");
       if (path) {
         const detected: DetectedGrid | null = await invoke(
           "detect_current_grid",
-          { dotaConfigPath: path },
+          { dotaConfigPath: path, gridHashes },
         );
-        if (detected && gridHashes) {
-          const matched: DetectedGrid | null = await invoke("match_grid_hash", {
-            gridHash: detected.hash,
-            gridHashes,
-          });
-          setDetectedGrid(matched || { ...detected, is_known: false });
-        }
+        setDetectedGrid(detected);
       }
     } catch (e: any) {
+      const error = e as AppError;
       notification.error({
-        title: "Failed to Apply Grid",
-        description: e.toString(),
+        title:
+          error.code === "DotaRunning"
+            ? "Please Close Dota First"
+            : error.code === "AntivirusSuspected"
+              ? "Grid Blocked by Antivirus"
+              : "Failed to Apply Grid",
+        description: errorMessage(e),
         placement: "bottomRight",
       });
     } finally {
@@ -129,17 +168,9 @@ function App() {
         // Re-run grid detection with the new path
         const detected: DetectedGrid | null = await invoke(
           "detect_current_grid",
-          { dotaConfigPath: selectedPath },
+          { dotaConfigPath: selectedPath, gridHashes },
         );
-        if (detected && gridHashes) {
-          const matched: DetectedGrid | null = await invoke("match_grid_hash", {
-            gridHash: detected.hash,
-            gridHashes,
-          });
-          setDetectedGrid(matched || { ...detected, is_known: false });
-        } else {
-          setDetectedGrid(null);
-        }
+        setDetectedGrid(detected);
 
         notification.success({
           title: "Path Updated",
@@ -150,7 +181,7 @@ function App() {
     } catch (e: any) {
       notification.error({
         title: "Failed to Select Path",
-        description: e.toString(),
+        description: errorMessage(e),
         placement: "bottomRight",
       });
     } finally {
@@ -357,10 +388,14 @@ function App() {
           minimizeToTray: loadedMinimizeToTray,
           startMinimized: loadedStartMinimized,
         });
-        await invoke("initialize_settings", {
-          minimizeToTray: loadedMinimizeToTray,
-          startMinimized: loadedStartMinimized,
-        });
+        // The backend store wins; localStorage only seeds it the first time
+        const stored: { minimize_to_tray: boolean; start_minimized: boolean } =
+          await invoke("initialize_settings", {
+            minimizeToTray: loadedMinimizeToTray,
+            startMinimized: loadedStartMinimized,
+          });
+        setMinimizeToTray(stored.minimize_to_tray);
+        setStartMinimized(stored.start_minimized);
 
         // Window visibility is now handled by the Rust backend during setup
       } catch (error) {
@@ -378,7 +413,7 @@ function App() {
         // Initialize DPI information
         await updateDpiInfo();
 
-        const path: string | null = await invoke("find_dota_config_path");
+        const path: string | null = await invoke("get_dota_config_path");
         setDotaPath(path ?? "Dota 2 configuration path not found.");
 
         // Download grid hashes for detection
@@ -389,24 +424,15 @@ function App() {
         if (path) {
           const detected: DetectedGrid | null = await invoke(
             "detect_current_grid",
-            { dotaConfigPath: path },
+            { dotaConfigPath: path, gridHashes: hashes },
           );
-          if (detected) {
-            // Match the detected grid against known hashes
-            const matched: DetectedGrid | null = await invoke(
-              "match_grid_hash",
-              {
-                gridHash: detected.hash,
-                gridHashes: hashes,
-              },
-            );
-            setDetectedGrid(matched || { ...detected, is_known: false });
-          } else {
-            setDetectedGrid(null);
-          }
+          setDetectedGrid(detected);
         }
 
-        const remoteGrids: Grid[] = await invoke("list_remote_grids");
+        const { grids: remoteGrids }: RemoteGridList = await invoke(
+          "list_remote_grids",
+          { force: false },
+        );
         const grouped = remoteGrids.reduce(
           (acc, grid) => {
             const { date, name } = grid;
@@ -437,10 +463,10 @@ function App() {
           Object.values(grouped).sort((a, b) => b.date.localeCompare(a.date)),
         );
       } catch (e: any) {
-        setError(e.toString());
+        setError(errorMessage(e));
         notification.error({
           title: "Error During Initialization",
-          description: e.toString(),
+          description: errorMessage(e),
           placement: "bottomRight" as const,
         });
       } finally {
@@ -723,17 +749,19 @@ function App() {
                   style={{ marginTop: "16px" }}
                   onClick={async () => {
                     try {
-                      await invoke("clear_cache");
+                      const report: {
+                        files_removed: number;
+                        bytes_removed: number;
+                      } = await invoke("clear_cache", { purgeBackups: false });
                       notification.success({
                         title: "Cache Cleared",
-                        description:
-                          "Local cache has been cleared successfully.",
+                        description: `Removed ${report.files_removed} file(s), ${report.bytes_removed} bytes.`,
                         placement: "bottomRight",
                       });
                     } catch (e: any) {
                       notification.error({
                         title: "Failed to Clear Cache",
-                        description: e.toString(),
+                        description: errorMessage(e),
                         placement: "bottomRight",
                       });
                     }